@@ -0,0 +1,54 @@
+//! Compares the serial and parallel column casters.
+//!
+//! Run with `cargo bench --bench cast_rays`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use wolf3d_rs::map::Map;
+use wolf3d_rs::raycaster::{cast_rays_parallel, cast_rays_serial, FOV};
+
+const COLUMNS: [usize; 3] = [640, 1920, 3840];
+const FRAMES: u32 = 200;
+
+fn time_frames<F: FnMut(u32)>(mut f: F) -> Duration {
+    let start = Instant::now();
+    for frame in 0..FRAMES {
+        f(frame);
+    }
+    start.elapsed() / FRAMES
+}
+
+fn main() {
+    let mut map = Map::load("map1").expect("map1 loads");
+    let doors = map.spawn_doors();
+    let (px, py, _) = map.player_start;
+
+    for &columns in &COLUMNS {
+        // Both paths must agree before their timings mean anything.
+        for frame in 0..16 {
+            let angle = frame as f32 * 0.4;
+            assert_eq!(
+                cast_rays_serial(px, py, angle, FOV, &map, &doors, columns),
+                cast_rays_parallel(px, py, angle, FOV, &map, &doors, columns),
+                "serial and parallel casts differ at {columns} columns"
+            );
+        }
+
+        let serial = time_frames(|frame| {
+            let angle = frame as f32 * 0.01;
+            black_box(cast_rays_serial(px, py, angle, FOV, &map, &doors, columns));
+        });
+        let parallel = time_frames(|frame| {
+            let angle = frame as f32 * 0.01;
+            black_box(cast_rays_parallel(px, py, angle, FOV, &map, &doors, columns));
+        });
+
+        println!(
+            "{columns:>5} columns: serial {:>9.1?}  parallel {:>9.1?}  speedup {:.2}x",
+            serial,
+            parallel,
+            serial.as_secs_f64() / parallel.as_secs_f64(),
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use crate::bestiary::Bestiary;
+use crate::difficulty::Difficulty;
+use crate::door::{DoorAction, DOOR_PASSABLE};
+use crate::entity::EntityId;
+use crate::items::Item;
+use crate::map::{MAP_HEIGHT, MAP_WIDTH};
+use crate::world::World;
+
+/// Tiles walked per tick by a patrolling enemy.
+pub const PATROL_SPEED: f32 = 1.0 / 32.0;
+
+/// Compass direction on the map grid; north is up, towards row 0.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Dir {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Dir {
+    /// Map token: `^`, `>`, `v` or `<`.
+    pub fn from_char(c: char) -> Option<Self> {
+        Some(match c {
+            '^' => Dir::North,
+            '>' => Dir::East,
+            'v' => Dir::South,
+            '<' => Dir::West,
+            _ => return None,
+        })
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Dir::North => '^',
+            Dir::East => '>',
+            Dir::South => 'v',
+            Dir::West => '<',
+        }
+    }
+
+    pub fn step(self) -> (i32, i32) {
+        match self {
+            Dir::North => (0, -1),
+            Dir::East => (1, 0),
+            Dir::South => (0, 1),
+            Dir::West => (-1, 0),
+        }
+    }
+
+    pub fn opposite(self) -> Self {
+        match self {
+            Dir::North => Dir::South,
+            Dir::East => Dir::West,
+            Dir::South => Dir::North,
+            Dir::West => Dir::East,
+        }
+    }
+}
+
+/// How an enemy behaves before it notices the player.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ActorState {
+    /// Stands still facing one way.
+    Standing,
+    /// Walks the way it faces, turning at turn markers and doubling back at walls.
+    Patrol,
+    /// Deaf: ignores gunfire and only reacts to seeing the player.
+    Ambush,
+}
+
+#[derive(Clone, Debug)]
+pub struct Enemy {
+    /// Index into the world's `Bestiary`.
+    pub kind: usize,
+    pub x: f32,
+    pub y: f32,
+    pub alive: bool,
+    /// Has heard or seen the player.
+    pub alert: bool,
+    pub facing: Dir,
+    pub state: ActorState,
+    pub health: i32,
+    /// Ticks until the enemy can attack again.
+    pub cooldown: u32,
+}
+
+impl Enemy {
+    pub fn tile(&self) -> (i32, i32) {
+        (self.x.floor() as i32, self.y.floor() as i32)
+    }
+}
+
+/// What a hit did to an enemy.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EnemyHit {
+    Hurt,
+    /// Health fell into the numbered phase of a multi-phase kind.
+    NewPhase(usize),
+    Killed,
+}
+
+/// Parses the enemy layer. Tokens are a kind token from the bestiary followed by any of a
+/// facing (`^ > v <`), `p` (patrol) or `a` (ambush), and a minimum difficulty (`e`, `m`, `h`).
+/// Lone facings are turn markers, which belong to the map and are skipped here.
+pub fn parse_enemies(content: &str, difficulty: Difficulty, bestiary: &Bestiary) -> Vec<Enemy> {
+    let mut enemies = Vec::new();
+    let mut row = 0usize;
+    for line in content.lines() {
+        if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+        if row >= MAP_HEIGHT {
+            break;
+        }
+        for (col, token) in line.split_whitespace().enumerate() {
+            if col >= MAP_WIDTH {
+                break;
+            }
+            let mut chars = token.chars();
+            let Some(first) = chars.next() else { continue };
+            let Some(kind) = bestiary.by_token(first) else { continue };
+            let (mut facing, mut state, mut min) = (Dir::East, ActorState::Standing, Difficulty::Baby);
+            let mut valid = true;
+            for c in chars {
+                match c {
+                    'p' => state = ActorState::Patrol,
+                    'a' => state = ActorState::Ambush,
+                    c => match (Dir::from_char(c), Difficulty::from_tag(c.encode_utf8(&mut [0; 4]))) {
+                        (Some(dir), _) => facing = dir,
+                        (None, Some(d)) => min = d,
+                        (None, None) => valid = false,
+                    },
+                }
+            }
+            if !valid || min > difficulty {
+                continue;
+            }
+            enemies.push(Enemy {
+                kind,
+                x: col as f32 + 0.5,
+                y: row as f32 + 0.5,
+                alive: true,
+                alert: false,
+                facing,
+                state,
+                health: bestiary.get(kind).health,
+                cooldown: 0,
+            });
+        }
+        row += 1;
+    }
+    enemies
+}
+
+/// Moves coordinate `v` by `PATROL_SPEED` in direction `d`, stopping on the next tile centre.
+fn step_toward_centre(v: f32, d: i32) -> f32 {
+    match d {
+        1 => (v + PATROL_SPEED).min((v - 0.5).floor() + 1.5),
+        -1 => (v - PATROL_SPEED).max((v + 0.5).ceil() - 1.5),
+        _ => v,
+    }
+}
+
+impl World {
+    /// Alerts every live enemy that can hear a noise made at `(x, y)`: those standing in an area
+    /// connected to it through open doors, unless lying in ambush. Returns the enemies newly alerted.
+    pub fn alert_enemies(&mut self, x: f32, y: f32) -> Vec<EntityId> {
+        let map = &self.map;
+        let Some(from) = map.areas.area_at(map, x, y) else {
+            return Vec::new();
+        };
+        let reachable = map.areas.reachable(map, &self.doors, from);
+        let heard: Vec<EntityId> = self
+            .enemies
+            .iter()
+            .filter(|(_, e)| e.alive && !e.alert && e.state != ActorState::Ambush)
+            .filter(|(_, e)| map.areas.area_at(map, e.x, e.y).is_some_and(|a| reachable[a as usize]))
+            .map(|(id, _)| id)
+            .collect();
+        for &id in &heard {
+            self.enemies[id].alert = true;
+        }
+        heard
+    }
+
+    /// Alerts every live enemy that can see the point `(x, y)`: it lies in front of them and
+    /// nothing blocks the line between. Returns the enemies newly alerted.
+    pub fn sight_alerts(&mut self, x: f32, y: f32) -> Vec<EntityId> {
+        let map = &self.map;
+        let seen: Vec<EntityId> = self
+            .enemies
+            .iter()
+            .filter(|(_, e)| e.alive && !e.alert)
+            .filter(|(_, e)| {
+                let (fx, fy) = e.facing.step();
+                (x - e.x) * fx as f32 + (y - e.y) * fy as f32 > 0.0 && map.line_of_sight(&self.doors, (e.x, e.y), (x, y))
+            })
+            .map(|(id, _)| id)
+            .collect();
+        for &id in &seen {
+            self.enemies[id].alert = true;
+        }
+        seen
+    }
+
+    /// Takes `amount` health off enemy `id` and alerts it, dropping its item if it dies.
+    pub fn damage_enemy(&mut self, id: EntityId, amount: i32) -> EnemyHit {
+        let def = self.bestiary.get(self.enemies[id].kind);
+        let e = &mut self.enemies[id];
+        if !e.alive {
+            return EnemyHit::Killed;
+        }
+        let phase = def.phase_index(e.health);
+        e.health -= amount;
+        e.alert = true;
+        if e.health <= 0 {
+            e.health = 0;
+            e.alive = false;
+            if let Some(kind) = def.drop {
+                let (x, y) = (e.x.floor() + 0.5, e.y.floor() + 0.5);
+                self.items.spawn(Item { kind, x, y, picked: false, dropped: true });
+            }
+            return EnemyHit::Killed;
+        }
+        match def.phase_index(e.health) {
+            p if p != phase => EnemyHit::NewPhase(p),
+            _ => EnemyHit::Hurt,
+        }
+    }
+
+    /// Walks every unalerted patrolling enemy one tick. At each tile centre it takes any turn
+    /// marker there, opens a closed door ahead and waits for it, or turns back at a wall.
+    pub fn update_patrols(&mut self) {
+        for id in self.enemies.ids() {
+            let e = &self.enemies[id];
+            if !e.alive || e.alert || e.state != ActorState::Patrol {
+                continue;
+            }
+            let (tx, ty) = e.tile();
+            let at_centre = e.x == tx as f32 + 0.5 && e.y == ty as f32 + 0.5;
+            if at_centre {
+                if let Some(dir) = self.map.turns[ty as usize][tx as usize] {
+                    self.enemies[id].facing = dir;
+                }
+                let (dx, dy) = self.enemies[id].facing.step();
+                let (nx, ny) = (tx + dx, ty + dy);
+                if let Some(door) = self.map.door_at_mut(&mut self.doors, nx, ny) {
+                    if door.open < DOOR_PASSABLE {
+                        if matches!(door.action, DoorAction::Closed | DoorAction::Closing) {
+                            door.activate();
+                        }
+                        continue;
+                    }
+                } else if self.map.is_solid(&self.doors, nx, ny) {
+                    let e = &mut self.enemies[id];
+                    e.facing = e.facing.opposite();
+                    continue;
+                }
+            }
+            let e = &mut self.enemies[id];
+            let (dx, dy) = e.facing.step();
+            e.x = step_toward_centre(e.x, dx);
+            e.y = step_toward_centre(e.y, dy);
+        }
+    }
+}
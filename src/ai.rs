@@ -0,0 +1,77 @@
+use crate::bestiary::Attack;
+use crate::door::{DoorAction, DOOR_PASSABLE};
+use crate::entity::EntityId;
+use crate::projectile::{Owner, Projectile};
+use crate::world::World;
+
+/// Reach of a melee attack, centre to centre.
+pub const MELEE_RANGE: f32 = 1.0;
+/// Furthest a hitscan or projectile attacker will shoot from.
+pub const SHOT_RANGE: f32 = 10.0;
+
+/// Runs one tick of every alerted enemy: attack the player when in reach and sight (projectile
+/// attackers spawn into `World::projectiles`), otherwise
+/// chase along the flow field toward the player's tile, opening doors on the way.
+/// Hitscan shots may miss with distance, and melee and hitscan damage is rolled from `World::rng`.
+/// Returns the enemies that attacked this tick, hit or miss.
+pub fn update(world: &mut World) -> Vec<EntityId> {
+    let World { map, doors, bestiary, player, enemies, projectiles, flow, difficulty, rng, .. } = world;
+    let mut attacks = Vec::new();
+    let target = (player.x.floor() as i32, player.y.floor() as i32);
+
+    for i in enemies.ids() {
+        let e = &enemies[i];
+        if !e.alive || !e.alert {
+            continue;
+        }
+        let phase = *bestiary.get(e.kind).phase(e.health);
+        let (ex, ey) = (e.x, e.y);
+        let dist = ((player.x - ex).powi(2) + (player.y - ey).powi(2)).sqrt();
+        let reach = match phase.attack {
+            Attack::Melee => MELEE_RANGE,
+            Attack::Hitscan | Attack::Projectile(_) => SHOT_RANGE,
+        };
+        enemies[i].cooldown = enemies[i].cooldown.saturating_sub(1);
+
+        if player.is_alive() && dist <= reach && map.line_of_sight(doors, (ex, ey), (player.x, player.y)) {
+            if enemies[i].cooldown == 0 {
+                enemies[i].cooldown = phase.cooldown;
+                match phase.attack {
+                    Attack::Projectile(kind) => {
+                        let shot = Projectile::aimed(kind, Owner::Enemy(i), phase.damage, (ex, ey), (player.x, player.y));
+                        projectiles.spawn(shot);
+                    }
+                    Attack::Hitscan if !rng.hits(dist) => {}
+                    Attack::Melee | Attack::Hitscan => {
+                        player.damage(rng.damage(phase.damage), *difficulty);
+                    }
+                }
+                attacks.push(i);
+            }
+            continue;
+        }
+
+        let (tx, ty) = (ex.floor() as i32, ey.floor() as i32);
+        let Some((dx, dy)) = flow.toward(map, doors, target).direction(map, doors, tx, ty) else { continue };
+        let (nx, ny) = (tx + dx, ty + dy);
+        if let Some(door) = map.door_at_mut(doors, nx, ny) {
+            if door.open < DOOR_PASSABLE {
+                if matches!(door.action, DoorAction::Closed | DoorAction::Closing) {
+                    door.activate();
+                }
+                continue;
+            }
+        }
+        // Head for the middle of the next tile; the flow field never routes across a corner.
+        let (gx, gy) = (nx as f32 + 0.5, ny as f32 + 0.5);
+        let to_goal = ((gx - ex).powi(2) + (gy - ey).powi(2)).sqrt();
+        let e = &mut enemies[i];
+        if to_goal <= phase.speed {
+            (e.x, e.y) = (gx, gy);
+        } else {
+            e.x += (gx - ex) / to_goal * phase.speed;
+            e.y += (gy - ey) / to_goal * phase.speed;
+        }
+    }
+    attacks
+}
@@ -0,0 +1,106 @@
+use std::collections::VecDeque;
+
+use crate::door::{Door, DoorAction};
+use crate::entity::Entities;
+use crate::map::{Cell, Map, MAP_HEIGHT, MAP_WIDTH};
+
+/// Floor tiles split into areas, Wolf3D style: flood-filled regions of open floor with doors as
+/// the only links between them. Sound carries between two areas while a door joining them is
+/// not fully closed.
+#[derive(Clone, Debug)]
+pub struct Areas {
+    tiles: [[Option<u16>; MAP_WIDTH]; MAP_HEIGHT],
+    count: usize,
+    /// `(door tile, area, area)` for every pair of areas a door joins.
+    links: Vec<((i32, i32), u16, u16)>,
+}
+
+impl Areas {
+    /// Flood-fills the wall grid. Doors, walls and exit switches bound the areas.
+    pub fn compute(walls: &[[Cell; MAP_WIDTH]; MAP_HEIGHT]) -> Self {
+        let empty = |x: i32, y: i32| {
+            x >= 0 && y >= 0 && x < MAP_WIDTH as i32 && y < MAP_HEIGHT as i32 && walls[y as usize][x as usize] == Cell::Empty
+        };
+        let mut tiles = [[None; MAP_WIDTH]; MAP_HEIGHT];
+        let mut count = 0;
+        for sy in 0..MAP_HEIGHT {
+            for sx in 0..MAP_WIDTH {
+                if walls[sy][sx] != Cell::Empty || tiles[sy][sx].is_some() {
+                    continue;
+                }
+                let id = count as u16;
+                count += 1;
+                tiles[sy][sx] = Some(id);
+                let mut queue = VecDeque::from([(sx as i32, sy as i32)]);
+                while let Some((x, y)) = queue.pop_front() {
+                    for (nx, ny) in neighbours(x, y) {
+                        if empty(nx, ny) && tiles[ny as usize][nx as usize].is_none() {
+                            tiles[ny as usize][nx as usize] = Some(id);
+                            queue.push_back((nx, ny));
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut areas = Self { tiles, count, links: Vec::new() };
+        let doors = (0..MAP_HEIGHT as i32)
+            .flat_map(|y| (0..MAP_WIDTH as i32).map(move |x| (x, y)))
+            .filter(|&(x, y)| matches!(walls[y as usize][x as usize], Cell::Door(_)));
+        for door in doors {
+            let mut sides: Vec<u16> = neighbours(door.0, door.1).filter_map(|(x, y)| areas.tile_area(x, y)).collect();
+            sides.sort_unstable();
+            sides.dedup();
+            for (n, &a) in sides.iter().enumerate() {
+                for &b in &sides[n + 1..] {
+                    areas.links.push((door, a, b));
+                }
+            }
+        }
+        areas
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    fn tile_area(&self, x: i32, y: i32) -> Option<u16> {
+        if x < 0 || y < 0 || x >= MAP_WIDTH as i32 || y >= MAP_HEIGHT as i32 {
+            return None;
+        }
+        self.tiles[y as usize][x as usize]
+    }
+
+    /// Area containing the point `(x, y)`. Someone standing in a doorway counts as being in
+    /// the first area beside the door.
+    pub fn area_at(&self, map: &Map, x: f32, y: f32) -> Option<u16> {
+        let (tx, ty) = (x.floor() as i32, y.floor() as i32);
+        self.tile_area(tx, ty).or_else(|| {
+            map.is_door(tx, ty).then(|| neighbours(tx, ty).find_map(|(nx, ny)| self.tile_area(nx, ny))).flatten()
+        })
+    }
+
+    /// Which areas sound made in `from` reaches through the doors as they stand now.
+    pub fn reachable(&self, map: &Map, doors: &Entities<Door>, from: u16) -> Vec<bool> {
+        let mut seen = vec![false; self.count];
+        seen[from as usize] = true;
+        let mut queue = VecDeque::from([from]);
+        while let Some(area) = queue.pop_front() {
+            for &((x, y), a, b) in &self.links {
+                if map.door_at(doors, x, y).is_none_or(|d| d.action == DoorAction::Closed) {
+                    continue;
+                }
+                let next = if a == area { b } else if b == area { a } else { continue };
+                if !seen[next as usize] {
+                    seen[next as usize] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+        seen
+    }
+}
+
+fn neighbours(x: i32, y: i32) -> impl Iterator<Item = (i32, i32)> {
+    [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)].into_iter()
+}
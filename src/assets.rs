@@ -0,0 +1,32 @@
+/// Directory data files are read from at runtime on native builds.
+pub const ASSET_DIR: &str = "maps";
+
+/// Data files compiled into the binary. The web build can only use these;
+/// native builds fall back to them when a file is missing from `ASSET_DIR`.
+const EMBEDDED: &[(&str, &str)] = &[
+    ("episode1.txt", include_str!("../maps/episode1.txt")),
+    ("enemies.txt", include_str!("../maps/enemies.txt")),
+    ("map1_walls.txt", include_str!("../maps/map1_walls.txt")),
+    ("map1_enemies.txt", include_str!("../maps/map1_enemies.txt")),
+    ("map1_spawn.txt", include_str!("../maps/map1_spawn.txt")),
+    ("map1_meta.txt", include_str!("../maps/map1_meta.txt")),
+    ("map1_lights.txt", include_str!("../maps/map1_lights.txt")),
+    ("map1_items.txt", include_str!("../maps/map1_items.txt")),
+    ("map2_walls.txt", include_str!("../maps/map2_walls.txt")),
+    ("map2_enemies.txt", include_str!("../maps/map2_enemies.txt")),
+    ("map2_spawn.txt", include_str!("../maps/map2_spawn.txt")),
+    ("map2_meta.txt", include_str!("../maps/map2_meta.txt")),
+    ("map2_items.txt", include_str!("../maps/map2_items.txt")),
+    ("secret1_walls.txt", include_str!("../maps/secret1_walls.txt")),
+    ("secret1_spawn.txt", include_str!("../maps/secret1_spawn.txt")),
+    ("secret1_items.txt", include_str!("../maps/secret1_items.txt")),
+];
+
+/// Reads the data file `name`, preferring a copy on disk so maps can be edited without rebuilding.
+pub fn load(name: &str) -> Option<String> {
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Ok(text) = std::fs::read_to_string(std::path::Path::new(ASSET_DIR).join(name)) {
+        return Some(text);
+    }
+    EMBEDDED.iter().find(|(n, _)| *n == name).map(|(_, text)| text.to_string())
+}
@@ -0,0 +1,415 @@
+use std::cell::RefCell;
+use std::f32::consts::FRAC_PI_4;
+use std::rc::Rc;
+
+use crate::door::{Door, DoorAction};
+use crate::entity::Entities;
+use crate::player::Player;
+
+/// Distance in tiles at which a positional sound drops to half volume.
+pub const FALLOFF_DIST: f32 = 4.0;
+
+/// One-shot sound effects. There are no sample files; each is synthesised from a `Tone`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SoundId {
+    Gunfire,
+    Knife,
+    DoorOpen,
+    DoorClose,
+    PushWall,
+    Pickup,
+    /// An enemy spotting or hearing the player.
+    Alert,
+    EnemyDeath,
+    PlayerDeath,
+    Bark,
+    /// A rocket, fireball or syringe leaving its launcher.
+    Launch,
+    Explosion,
+    /// Trying a locked door without its key.
+    Denied,
+}
+
+/// Looping background tracks.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Music {
+    Level,
+    Intermission,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Wave {
+    Square,
+    Noise,
+}
+
+/// A sweep from `start_hz` to `end_hz` over `secs`, fading out linearly.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Tone {
+    pub wave: Wave,
+    pub start_hz: f32,
+    pub end_hz: f32,
+    pub secs: f32,
+    pub volume: f32,
+}
+
+impl SoundId {
+    pub fn tone(self) -> Tone {
+        let (wave, start_hz, end_hz, secs, volume) = match self {
+            SoundId::Gunfire => (Wave::Noise, 1800.0, 300.0, 0.18, 0.6),
+            SoundId::Knife => (Wave::Noise, 600.0, 200.0, 0.08, 0.3),
+            SoundId::DoorOpen => (Wave::Square, 90.0, 140.0, 0.5, 0.25),
+            SoundId::DoorClose => (Wave::Square, 140.0, 80.0, 0.5, 0.25),
+            SoundId::PushWall => (Wave::Noise, 120.0, 60.0, 1.0, 0.35),
+            SoundId::Pickup => (Wave::Square, 880.0, 1320.0, 0.12, 0.3),
+            SoundId::Alert => (Wave::Square, 330.0, 520.0, 0.3, 0.4),
+            SoundId::EnemyDeath => (Wave::Square, 420.0, 60.0, 0.6, 0.45),
+            SoundId::PlayerDeath => (Wave::Square, 300.0, 40.0, 1.2, 0.5),
+            SoundId::Bark => (Wave::Noise, 900.0, 500.0, 0.15, 0.45),
+            SoundId::Launch => (Wave::Noise, 400.0, 900.0, 0.3, 0.4),
+            SoundId::Explosion => (Wave::Noise, 300.0, 30.0, 0.9, 0.7),
+            SoundId::Denied => (Wave::Square, 180.0, 150.0, 0.25, 0.35),
+        };
+        Tone { wave, start_hz, end_hz, secs, volume }
+    }
+
+    /// Name used for the sound in data files such as the bestiary.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "gunfire" => SoundId::Gunfire,
+            "knife" => SoundId::Knife,
+            "door_open" => SoundId::DoorOpen,
+            "door_close" => SoundId::DoorClose,
+            "pushwall" => SoundId::PushWall,
+            "pickup" => SoundId::Pickup,
+            "alert" => SoundId::Alert,
+            "enemy_death" => SoundId::EnemyDeath,
+            "player_death" => SoundId::PlayerDeath,
+            "bark" => SoundId::Bark,
+            "launch" => SoundId::Launch,
+            "explosion" => SoundId::Explosion,
+            "denied" => SoundId::Denied,
+            _ => return None,
+        })
+    }
+}
+
+impl Music {
+    /// `(midi note, beats)` pairs played in a loop; note 0 is a rest.
+    fn notes(self) -> &'static [(u8, f32)] {
+        match self {
+            Music::Level => &[
+                (57, 1.0), (60, 1.0), (64, 1.0), (60, 1.0), (62, 1.0), (65, 1.0), (69, 2.0),
+                (67, 1.0), (64, 1.0), (60, 1.0), (64, 1.0), (62, 2.0), (0, 2.0),
+            ],
+            Music::Intermission => &[
+                (60, 1.0), (64, 1.0), (67, 1.0), (72, 3.0), (0, 1.0), (67, 1.0), (72, 4.0), (0, 4.0),
+            ],
+        }
+    }
+
+    fn beats_per_sec(self) -> f32 {
+        match self {
+            Music::Level => 3.0,
+            Music::Intermission => 2.0,
+        }
+    }
+}
+
+/// Where a sound sits relative to the listener.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Spatial {
+    /// Volume multiplier, 0.0 - 1.0.
+    pub gain: f32,
+    /// -1.0 is hard left, 1.0 hard right.
+    pub pan: f32,
+}
+
+impl Spatial {
+    /// Full volume, dead ahead: the player's own weapon, pickups and so on.
+    pub const CENTER: Spatial = Spatial { gain: 1.0, pan: 0.0 };
+
+    /// A sound at `(x, y)` heard by someone at `listener` = `(x, y, angle)`. Volume falls off
+    /// with distance; sounds to the right of the facing direction pan right.
+    pub fn at(listener: (f32, f32, f32), x: f32, y: f32) -> Self {
+        let (lx, ly, angle) = listener;
+        let (dx, dy) = (x - lx, y - ly);
+        let dist = (dx * dx + dy * dy).sqrt();
+        let gain = 1.0 / (1.0 + dist / FALLOFF_DIST);
+        // Angles grow clockwise on screen, so a positive bearing is to the listener's right.
+        let pan = if dist > 1e-3 { (dy.atan2(dx) - angle).sin() } else { 0.0 };
+        Spatial { gain, pan }
+    }
+
+    /// Equal-power `[left, right]` channel gains.
+    pub fn channel_gains(self) -> [f32; 2] {
+        let a = (self.pan.clamp(-1.0, 1.0) + 1.0) * FRAC_PI_4;
+        [self.gain * a.cos(), self.gain * a.sin()]
+    }
+}
+
+/// Everything sent to a backend, in the order it happened.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AudioEvent {
+    Sound(SoundId, Spatial),
+    /// Switch tracks; `None` stops the music.
+    Music(Option<Music>),
+}
+
+pub trait AudioBackend {
+    /// Plays `event`, issued on game tick `tick`.
+    fn send(&mut self, tick: u32, event: AudioEvent);
+}
+
+/// Discards everything. Used when there is no sound device, and on the web build.
+pub struct NullBackend;
+
+impl AudioBackend for NullBackend {
+    fn send(&mut self, _tick: u32, _event: AudioEvent) {}
+}
+
+/// Keeps a log of every event and the tick it was issued on, for tests. Clones share the log,
+/// so a test can hand one to `Audio` and inspect another.
+#[derive(Clone, Default)]
+pub struct RecordingBackend {
+    log: Rc<RefCell<Vec<(u32, AudioEvent)>>>,
+}
+
+impl RecordingBackend {
+    pub fn events(&self) -> Vec<(u32, AudioEvent)> {
+        self.log.borrow().clone()
+    }
+
+    /// Just the sound effects, with their ticks.
+    pub fn sounds(&self) -> Vec<(u32, SoundId)> {
+        self.log
+            .borrow()
+            .iter()
+            .filter_map(|&(tick, e)| match e {
+                AudioEvent::Sound(s, _) => Some((tick, s)),
+                AudioEvent::Music(_) => None,
+            })
+            .collect()
+    }
+}
+
+impl AudioBackend for RecordingBackend {
+    fn send(&mut self, tick: u32, event: AudioEvent) {
+        self.log.borrow_mut().push((tick, event));
+    }
+}
+
+/// Front end the game talks to: stamps events with the current tick, places positional sounds
+/// around the listener and drops redundant music changes before handing them to the backend.
+pub struct Audio {
+    backend: Box<dyn AudioBackend>,
+    tick: u32,
+    music: Option<Music>,
+    /// `(x, y, angle)` of whoever is listening.
+    listener: (f32, f32, f32),
+}
+
+impl Audio {
+    pub fn new(backend: Box<dyn AudioBackend>) -> Self {
+        Self { backend, tick: 0, music: None, listener: (0.0, 0.0, 0.0) }
+    }
+
+    /// The sound device when built with the `audio` feature and one is available, else silence.
+    pub fn open_default() -> Self {
+        #[cfg(all(feature = "audio", not(target_arch = "wasm32")))]
+        match device::DeviceBackend::open() {
+            Ok(backend) => return Self::new(Box::new(backend)),
+            Err(e) => log::warn!("no audio: {e}"),
+        }
+        Self::new(Box::new(NullBackend))
+    }
+
+    pub fn tick(&self) -> u32 {
+        self.tick
+    }
+
+    pub fn advance(&mut self) {
+        self.tick = self.tick.wrapping_add(1);
+    }
+
+    pub fn set_listener(&mut self, player: &Player) {
+        self.listener = (player.x, player.y, player.angle);
+    }
+
+    /// Plays `sound` centred at full volume.
+    pub fn play(&mut self, sound: SoundId) {
+        self.backend.send(self.tick, AudioEvent::Sound(sound, Spatial::CENTER));
+    }
+
+    /// Plays `sound` as coming from `(x, y)`, panned and attenuated for the listener.
+    pub fn play_at(&mut self, sound: SoundId, x: f32, y: f32) {
+        let spatial = Spatial::at(self.listener, x, y);
+        self.backend.send(self.tick, AudioEvent::Sound(sound, spatial));
+    }
+
+    pub fn set_music(&mut self, music: Option<Music>) {
+        if music != self.music {
+            self.music = music;
+            self.backend.send(self.tick, AudioEvent::Music(music));
+        }
+    }
+}
+
+/// Sounds for doors whose slide changed direction since `before` was taken, in slot order, with
+/// the centre of the door each comes from.
+pub fn door_sounds(before: &[DoorAction], doors: &Entities<Door>) -> Vec<(SoundId, f32, f32)> {
+    before
+        .iter()
+        .zip(doors.values())
+        .filter(|(was, door)| std::mem::discriminant(*was) != std::mem::discriminant(&door.action))
+        .filter_map(|(_, door)| {
+            let sound = match door.action {
+                DoorAction::Opening => SoundId::DoorOpen,
+                DoorAction::Closing => SoundId::DoorClose,
+                DoorAction::Closed | DoorAction::Open { .. } => return None,
+            };
+            Some((sound, door.x as f32 + 0.5, door.y as f32 + 0.5))
+        })
+        .collect()
+}
+
+struct Voice {
+    tone: Tone,
+    /// `[left, right]` gains.
+    gains: [f32; 2],
+    /// Seconds since the voice started.
+    t: f32,
+    phase: f32,
+}
+
+/// Stereo software synthesiser behind the device backend. Produces one frame at a time.
+pub struct Mixer {
+    sample_rate: f32,
+    voices: Vec<Voice>,
+    music: Option<(Music, f32)>,
+    noise: u32,
+}
+
+impl Mixer {
+    pub fn new(sample_rate: f32) -> Self {
+        Self { sample_rate, voices: Vec::new(), music: None, noise: 0x1234_5678 }
+    }
+
+    pub fn handle(&mut self, event: AudioEvent) {
+        match event {
+            AudioEvent::Sound(sound, spatial) => {
+                self.voices.push(Voice { tone: sound.tone(), gains: spatial.channel_gains(), t: 0.0, phase: 0.0 })
+            }
+            AudioEvent::Music(music) => self.music = music.map(|m| (m, 0.0)),
+        }
+    }
+
+    /// The next `[left, right]` frame.
+    pub fn next_frame(&mut self) -> [f32; 2] {
+        let dt = 1.0 / self.sample_rate;
+        let mut out = [0.0; 2];
+
+        for v in &mut self.voices {
+            let k = v.t / v.tone.secs;
+            let hz = v.tone.start_hz + (v.tone.end_hz - v.tone.start_hz) * k;
+            v.phase = (v.phase + hz * dt).fract();
+            let s = match v.tone.wave {
+                Wave::Square => if v.phase < 0.5 { 1.0 } else { -1.0 },
+                Wave::Noise => {
+                    // Sample-and-hold noise: a new random level each cycle gives the sweep a pitch.
+                    if v.phase < hz * dt {
+                        self.noise ^= self.noise << 13;
+                        self.noise ^= self.noise >> 17;
+                        self.noise ^= self.noise << 5;
+                    }
+                    (self.noise >> 16) as f32 / 32768.0 - 1.0
+                }
+            };
+            let s = s * v.tone.volume * (1.0 - k);
+            out[0] += s * v.gains[0];
+            out[1] += s * v.gains[1];
+            v.t += dt;
+        }
+        self.voices.retain(|v| v.t < v.tone.secs);
+
+        if let Some((music, t)) = &mut self.music {
+            let notes = music.notes();
+            let total: f32 = notes.iter().map(|n| n.1).sum();
+            let mut beat = (*t * music.beats_per_sec()) % total;
+            for &(note, len) in notes {
+                if beat < len {
+                    if note != 0 {
+                        let hz = 440.0 * 2f32.powf((note as f32 - 69.0) / 12.0);
+                        let square = if (*t * hz).fract() < 0.5 { 1.0 } else { -1.0 };
+                        out[0] += square * 0.08;
+                        out[1] += square * 0.08;
+                    }
+                    break;
+                }
+                beat -= len;
+            }
+            *t += dt;
+        }
+
+        out.map(|s| s.clamp(-1.0, 1.0))
+    }
+}
+
+#[cfg(all(feature = "audio", not(target_arch = "wasm32")))]
+mod device {
+    use std::sync::mpsc::{self, Sender};
+
+    use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+
+    use super::{AudioBackend, AudioEvent, Mixer};
+
+    /// Plays through the default output device. Events cross to the audio thread over a channel.
+    pub struct DeviceBackend {
+        tx: Sender<AudioEvent>,
+        _stream: cpal::Stream,
+    }
+
+    impl DeviceBackend {
+        pub fn open() -> Result<Self, String> {
+            let host = cpal::default_host();
+            let device = host.default_output_device().ok_or("no output device")?;
+            let config: cpal::StreamConfig = device.default_output_config().map_err(|e| e.to_string())?.into();
+            let channels = config.channels as usize;
+            let mut mixer = Mixer::new(config.sample_rate.0 as f32);
+            let (tx, rx) = mpsc::channel();
+
+            let stream = device
+                .build_output_stream(
+                    &config,
+                    move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+                        while let Ok(event) = rx.try_recv() {
+                            mixer.handle(event);
+                        }
+                        for frame in data.chunks_mut(channels) {
+                            let [l, r] = mixer.next_frame();
+                            match frame {
+                                [mono] => *mono = (l + r) * 0.5,
+                                [left, right, rest @ ..] => {
+                                    *left = l;
+                                    *right = r;
+                                    rest.fill((l + r) * 0.5);
+                                }
+                                [] => {}
+                            }
+                        }
+                    },
+                    |e| log::error!("audio stream: {e}"),
+                    None,
+                )
+                .map_err(|e| e.to_string())?;
+            stream.play().map_err(|e| e.to_string())?;
+            Ok(Self { tx, _stream: stream })
+        }
+    }
+
+    impl AudioBackend for DeviceBackend {
+        fn send(&mut self, _tick: u32, event: AudioEvent) {
+            let _ = self.tx.send(event);
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::HashSet;
+use winit::keyboard::KeyCode;
+
+use crate::map::{MAP_WIDTH, MAP_HEIGHT};
+use crate::raycaster::RayHit;
+
+pub const MIN_ZOOM: f32 = 8.0;
+pub const MAX_ZOOM: f32 = 64.0;
+const ZOOM_STEP: f32 = 1.02;
+const PAN_SPEED: f32 = 0.15;
+
+/// Full-screen automap state. Only tiles that a cast ray has hit are ever revealed.
+pub struct Automap {
+    pub open: bool,
+    /// Keep the player centred; when off, the arrow keys pan the map instead of moving.
+    pub follow: bool,
+    /// Rotate the map so the player always faces up.
+    pub rotate: bool,
+    /// Pixels per tile.
+    pub zoom: f32,
+    pub pan_x: f32,
+    pub pan_y: f32,
+    pub seen: [[bool; MAP_WIDTH]; MAP_HEIGHT],
+    /// Floor tiles whose objects (keys) the player has had in view.
+    pub spotted: [[bool; MAP_WIDTH]; MAP_HEIGHT],
+}
+
+impl Automap {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            follow: true,
+            rotate: false,
+            zoom: 24.0,
+            pan_x: 0.0,
+            pan_y: 0.0,
+            seen: [[false; MAP_WIDTH]; MAP_HEIGHT],
+            spotted: [[false; MAP_WIDTH]; MAP_HEIGHT],
+        }
+    }
+
+    pub fn reveal(&mut self, hits: &[RayHit]) {
+        for hit in hits {
+            if hit.map_x >= 0 && hit.map_y >= 0 && (hit.map_x as usize) < MAP_WIDTH && (hit.map_y as usize) < MAP_HEIGHT {
+                self.seen[hit.map_y as usize][hit.map_x as usize] = true;
+            }
+        }
+    }
+
+    pub fn is_seen(&self, x: usize, y: usize) -> bool {
+        self.seen[y][x]
+    }
+
+    pub fn spot(&mut self, x: f32, y: f32) {
+        let (col, row) = (x as usize, y as usize);
+        if col < MAP_WIDTH && row < MAP_HEIGHT {
+            self.spotted[row][col] = true;
+        }
+    }
+
+    pub fn is_spotted(&self, x: usize, y: usize) -> bool {
+        x < MAP_WIDTH && y < MAP_HEIGHT && self.spotted[y][x]
+    }
+
+    /// Handles a key press. Returns true if the automap consumed it.
+    pub fn handle_key(&mut self, key: KeyCode, player_x: f32, player_y: f32) -> bool {
+        match key {
+            KeyCode::Tab => {
+                self.open = !self.open;
+                true
+            }
+            KeyCode::KeyF if self.open => {
+                self.follow = !self.follow;
+                self.pan_x = player_x;
+                self.pan_y = player_y;
+                true
+            }
+            KeyCode::KeyR if self.open => {
+                self.rotate = !self.rotate;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// True while the automap is steering the arrow keys.
+    pub fn captures_arrows(&self) -> bool {
+        self.open && !self.follow
+    }
+
+    pub fn update(&mut self, keys: &HashSet<KeyCode>) {
+        if !self.open {
+            return;
+        }
+        if keys.contains(&KeyCode::Equal) || keys.contains(&KeyCode::NumpadAdd) {
+            self.zoom = (self.zoom * ZOOM_STEP).min(MAX_ZOOM);
+        }
+        if keys.contains(&KeyCode::Minus) || keys.contains(&KeyCode::NumpadSubtract) {
+            self.zoom = (self.zoom / ZOOM_STEP).max(MIN_ZOOM);
+        }
+        if self.captures_arrows() {
+            if keys.contains(&KeyCode::ArrowLeft)  { self.pan_x -= PAN_SPEED; }
+            if keys.contains(&KeyCode::ArrowRight) { self.pan_x += PAN_SPEED; }
+            if keys.contains(&KeyCode::ArrowUp)    { self.pan_y -= PAN_SPEED; }
+            if keys.contains(&KeyCode::ArrowDown)  { self.pan_y += PAN_SPEED; }
+            self.pan_x = self.pan_x.clamp(0.0, MAP_WIDTH as f32);
+            self.pan_y = self.pan_y.clamp(0.0, MAP_HEIGHT as f32);
+        }
+    }
+}
+
+impl Default for Automap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,214 @@
+use crate::assets;
+use crate::audio::SoundId;
+use crate::items::ItemKind;
+use crate::projectile::ProjectileKind;
+
+/// Data file every enemy kind is defined in, under `maps/` like the levels.
+pub const BESTIARY_FILE: &str = "enemies.txt";
+/// Characters that mean something else in an enemy layer token and cannot name a kind.
+const RESERVED_TOKENS: &str = "0^>v<pabemh";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Attack {
+    /// Bites or stabs the player from the next tile.
+    Melee,
+    /// Shoots the player from anywhere in sight.
+    Hitscan,
+    /// Launches a projectile at the player from anywhere in sight.
+    Projectile(ProjectileKind),
+}
+
+/// Which built-in silhouette an enemy is drawn with; the kind's colour tints it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SpriteSet {
+    Soldier,
+    Dog,
+    Mutant,
+    Boss,
+}
+
+/// How an enemy fights while its health is at or below `below`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Phase {
+    pub below: i32,
+    /// Tiles per tick while chasing.
+    pub speed: f32,
+    pub attack: Attack,
+    pub damage: i32,
+    /// Ticks between attacks.
+    pub cooldown: u32,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct EnemyDef {
+    /// Character placing this kind in `<map>_enemies.txt`.
+    pub token: char,
+    pub name: String,
+    pub health: i32,
+    /// The first phase covers full health; the rest follow in order of falling health.
+    pub phases: Vec<Phase>,
+    pub drop: Option<ItemKind>,
+    pub sprite: SpriteSet,
+    pub color: [f32; 4],
+    pub alert_sound: SoundId,
+    pub attack_sound: SoundId,
+    pub death_sound: SoundId,
+    /// Points for the kill.
+    pub score: u32,
+}
+
+impl EnemyDef {
+    pub fn phase_index(&self, health: i32) -> usize {
+        self.phases.iter().rposition(|p| health <= p.below).unwrap_or(0)
+    }
+
+    pub fn phase(&self, health: i32) -> &Phase {
+        &self.phases[self.phase_index(health)]
+    }
+}
+
+/// Every enemy kind the game knows, as read from `BESTIARY_FILE`.
+#[derive(Clone, Debug, Default)]
+pub struct Bestiary {
+    kinds: Vec<EnemyDef>,
+}
+
+impl Bestiary {
+    pub fn load() -> Result<Self, String> {
+        let text = assets::load(BESTIARY_FILE).ok_or_else(|| format!("missing {BESTIARY_FILE}"))?;
+        Self::parse(&text).map_err(|e| format!("{BESTIARY_FILE}: {e}"))
+    }
+
+    /// Parses blocks of `key = value` lines, each opened by `kind = <token> <name>`:
+    ///
+    /// - `health = <hp>`
+    /// - `attack = <attack> <speed> <damage> <cooldown>`: the full-health phase
+    /// - `phase = <below hp> <attack> <speed> <damage> <cooldown>`: a later phase
+    ///
+    /// An attack is `melee`, `hitscan` or the name of a `ProjectileKind`.
+    /// - `drop = <item token>`
+    /// - `sprite = <soldier|dog|mutant|boss> <r> <g> <b>`
+    /// - `sounds = <alert> <attack> <death>`
+    /// - `score = <points>`
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut kinds: Vec<EnemyDef> = Vec::new();
+        let mut have_attack = Vec::new();
+
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.starts_with('#') || line.is_empty() {
+                continue;
+            }
+            let bad = |what: &str| format!("line {}: {what}: {line}", n + 1);
+            let (key, value) = line.split_once('=').ok_or_else(|| bad("expected key = value"))?;
+            let args: Vec<&str> = value.split_whitespace().collect();
+            let key = key.trim();
+
+            if key == "kind" {
+                let [token, name] = args.as_slice() else { return Err(bad("expected kind = <token> <name>")) };
+                let mut chars = token.chars();
+                let token = match (chars.next(), chars.next()) {
+                    (Some(c), None) if !RESERVED_TOKENS.contains(c) => c,
+                    _ => return Err(bad("kind token must be one character, not one of 0^>v<pabemh")),
+                };
+                if kinds.iter().any(|k| k.token == token) {
+                    return Err(bad("duplicate kind token"));
+                }
+                kinds.push(EnemyDef {
+                    token,
+                    name: name.to_string(),
+                    health: 0,
+                    phases: Vec::new(),
+                    drop: None,
+                    sprite: SpriteSet::Soldier,
+                    color: [1.0; 4],
+                    alert_sound: SoundId::Alert,
+                    attack_sound: SoundId::Gunfire,
+                    death_sound: SoundId::EnemyDeath,
+                    score: 0,
+                });
+                have_attack.push(false);
+                continue;
+            }
+
+            let def = kinds.last_mut().ok_or_else(|| bad("expected kind = first"))?;
+            match (key, args.as_slice()) {
+                ("health", [hp]) => def.health = hp.parse().map_err(|_| bad("bad health"))?,
+                ("attack", [attack, speed, damage, cooldown]) => {
+                    let phase = parse_phase(i32::MAX, attack, speed, damage, cooldown).ok_or_else(|| bad("bad attack"))?;
+                    def.phases.insert(0, phase);
+                    *have_attack.last_mut().expect("pushed with the kind") = true;
+                }
+                ("phase", [below, attack, speed, damage, cooldown]) => {
+                    let below = below.parse().map_err(|_| bad("bad phase health"))?;
+                    let phase = parse_phase(below, attack, speed, damage, cooldown).ok_or_else(|| bad("bad phase"))?;
+                    if def.phases.last().is_some_and(|p| p.below <= below) {
+                        return Err(bad("phases must come in order of falling health"));
+                    }
+                    def.phases.push(phase);
+                }
+                ("drop", [token]) => def.drop = Some(ItemKind::from_token(token).ok_or_else(|| bad("unknown item"))?),
+                ("sprite", [set, r, g, b]) => {
+                    def.sprite = match *set {
+                        "soldier" => SpriteSet::Soldier,
+                        "dog" => SpriteSet::Dog,
+                        "mutant" => SpriteSet::Mutant,
+                        "boss" => SpriteSet::Boss,
+                        _ => return Err(bad("unknown sprite set")),
+                    };
+                    let channel = |c: &str| c.parse::<f32>().map_err(|_| bad("bad colour"));
+                    def.color = [channel(r)?, channel(g)?, channel(b)?, 1.0];
+                }
+                ("sounds", [alert, attack, death]) => {
+                    let sound = |s: &str| SoundId::from_name(s).ok_or_else(|| bad("unknown sound"));
+                    (def.alert_sound, def.attack_sound, def.death_sound) = (sound(alert)?, sound(attack)?, sound(death)?);
+                }
+                ("score", [points]) => def.score = points.parse().map_err(|_| bad("bad score"))?,
+                _ => return Err(bad("unknown or malformed setting")),
+            }
+        }
+
+        for (def, attack) in kinds.iter_mut().zip(have_attack) {
+            if def.health <= 0 || !attack {
+                return Err(format!("kind {} needs a health and an attack", def.name));
+            }
+            def.phases[0].below = def.health;
+            if def.phases.get(1).is_some_and(|p| p.below >= def.health) {
+                return Err(format!("kind {}: phases must start below full health", def.name));
+            }
+        }
+        Ok(Self { kinds })
+    }
+
+    /// Panics if `kind` did not come from this bestiary.
+    pub fn get(&self, kind: usize) -> &EnemyDef {
+        &self.kinds[kind]
+    }
+
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.kinds.iter().position(|k| k.name == name)
+    }
+
+    pub fn by_token(&self, token: char) -> Option<usize> {
+        self.kinds.iter().position(|k| k.token == token)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &EnemyDef> {
+        self.kinds.iter()
+    }
+}
+
+fn parse_phase(below: i32, attack: &str, speed: &str, damage: &str, cooldown: &str) -> Option<Phase> {
+    let attack = match attack {
+        "melee" => Attack::Melee,
+        "hitscan" => Attack::Hitscan,
+        name => Attack::Projectile(ProjectileKind::from_name(name)?),
+    };
+    Some(Phase {
+        below,
+        speed: speed.parse().ok()?,
+        attack,
+        damage: damage.parse().ok()?,
+        cooldown: cooldown.parse().ok()?,
+    })
+}
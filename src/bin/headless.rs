@@ -0,0 +1,143 @@
+//! Runs the game without a window, for simulation and soak tests.
+//!
+//! `headless [--map <name>] [--difficulty <token>] [--ticks <n>] [--hash-every <n>] [--record <file>] [script]`
+//! `headless --demo <file> [--hash-every <n>]`
+//!
+//! Plays `script` (see `game::parse_inputs`), idling once it runs out, for `--ticks` ticks or
+//! the length of the script. Prints the state hash every `--hash-every` ticks and at the end.
+//! Levels are continued from straight away; the run stops early once the episode is done.
+//! `--record` also writes the first level of the run to a demo file.
+//!
+//! `--demo` plays a demo file back instead, failing at the first tick that desyncs.
+
+use std::process::ExitCode;
+
+use wolf3d_rs::demo::{Demo, Playback};
+use wolf3d_rs::difficulty::Difficulty;
+use wolf3d_rs::episode::{Episode, LevelStats};
+use wolf3d_rs::game::{self, Game};
+
+struct Args {
+    map: Option<String>,
+    difficulty: Difficulty,
+    ticks: Option<usize>,
+    hash_every: Option<usize>,
+    script: Option<String>,
+    record: Option<String>,
+    demo: Option<String>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args { map: None, difficulty: Difficulty::Medium, ticks: None, hash_every: None, script: None, record: None, demo: None };
+    let mut it = std::env::args().skip(1);
+    while let Some(arg) = it.next() {
+        let mut value = || it.next().ok_or_else(|| format!("{arg} needs a value"));
+        match arg.as_str() {
+            "--map" => args.map = Some(value()?),
+            "--difficulty" => {
+                let token = value()?;
+                args.difficulty = Difficulty::from_token(&token).ok_or_else(|| format!("unknown difficulty {token}"))?;
+            }
+            "--ticks" => args.ticks = Some(value()?.parse().map_err(|_| "--ticks needs a number")?),
+            "--record" => args.record = Some(value()?),
+            "--demo" => args.demo = Some(value()?),
+            "--hash-every" => args.hash_every = Some(value()?.parse().map_err(|_| "--hash-every needs a number")?),
+            _ if arg.starts_with("--") => return Err(format!("unknown option {arg}")),
+            _ => args.script = Some(arg),
+        }
+    }
+    Ok(args)
+}
+
+fn run(args: Args) -> Result<(), String> {
+    if let Some(path) = &args.demo {
+        return play_demo(path, args.hash_every);
+    }
+    let inputs = match &args.script {
+        Some(path) => {
+            let text = std::fs::read_to_string(path).map_err(|e| format!("can't read {path}: {e}"))?;
+            game::parse_inputs(&text)?
+        }
+        None => Vec::new(),
+    };
+    let ticks = args.ticks.unwrap_or(inputs.len());
+    let mut game = Game::new(Episode::load(game::EPISODE)?, args.difficulty)?;
+    if let Some(map) = &args.map {
+        game.change_map(map)?;
+    }
+    let mut recording = args.record.as_ref().map(|_| {
+        let world = &game.world;
+        Demo::new(&world.name, world.difficulty, world.rng.state, world.move_speed, world.rot_speed)
+    });
+    if let Some(demo) = &recording {
+        // Start exactly the way playback will.
+        demo.start(&mut game)?;
+    }
+
+    for tick in 0..ticks {
+        let input = inputs.get(tick).copied().unwrap_or_default();
+        game.tick(&input);
+        if let Some(demo) = &mut recording {
+            demo.record(input, &game);
+        }
+        if let Some(stats) = game.intermission {
+            if let (Some(demo), Some(path)) = (recording.take(), &args.record) {
+                write_demo(path, &demo)?;
+            }
+            eprintln!(
+                "floor {} done after {} ticks: kills {}%, secrets {}%, treasure {}%",
+                stats.floor,
+                stats.ticks,
+                LevelStats::ratio(stats.kills, stats.kills_total),
+                LevelStats::ratio(stats.secrets, stats.secrets_total),
+                LevelStats::ratio(stats.treasure, stats.treasure_total),
+            );
+            if !game.continue_from_intermission()? {
+                eprintln!("episode done after {} ticks", tick + 1);
+                break;
+            }
+        }
+        if args.hash_every.is_some_and(|n| n > 0 && (tick + 1) % n == 0) {
+            println!("tick {} {:016x}", tick + 1, game.state_hash());
+        }
+    }
+    if let (Some(demo), Some(path)) = (recording, &args.record) {
+        write_demo(path, &demo)?;
+    }
+    println!("{:016x}", game.state_hash());
+    Ok(())
+}
+
+fn write_demo(path: &str, demo: &Demo) -> Result<(), String> {
+    std::fs::write(path, demo.to_text()).map_err(|e| format!("can't write {path}: {e}"))?;
+    eprintln!("recorded {} ticks to {path}", demo.inputs.len());
+    Ok(())
+}
+
+fn play_demo(path: &str, hash_every: Option<usize>) -> Result<(), String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("can't read {path}: {e}"))?;
+    let demo = Demo::parse(&text)?;
+    let mut game = Game::new(Episode::load(game::EPISODE)?, demo.difficulty)?;
+    let mut playback = Playback::start(demo, &mut game)?;
+    while playback.tick(&mut game).is_some() {
+        if let Some(tick) = playback.desync {
+            return Err(format!("demo desynced at tick {tick}: state {:016x}", game.state_hash()));
+        }
+        if hash_every.is_some_and(|n| n > 0 && playback.tick % n == 0) {
+            println!("tick {} {:016x}", playback.tick, game.state_hash());
+        }
+    }
+    eprintln!("demo played {} ticks in sync", playback.tick);
+    println!("{:016x}", game.state_hash());
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match parse_args().and_then(run) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("headless: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use std::time::Duration;
+
+use crate::episode::TICK_RATE;
+
+/// Most ticks one frame may catch up on. Time beyond this, such as a stall or a hidden window,
+/// is dropped rather than replayed in a burst.
+pub const MAX_TICKS_PER_FRAME: u32 = 10;
+
+/// Fixed-timestep accumulator turning real frame times into whole simulation ticks, so the
+/// game runs at `TICK_RATE` whatever the display's refresh rate.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct TickClock {
+    /// Elapsed time not yet spent on a tick.
+    pending: Duration,
+}
+
+impl TickClock {
+    /// Adds `elapsed` and returns how many ticks are now due.
+    pub fn advance(&mut self, elapsed: Duration) -> u32 {
+        let tick = Duration::from_secs(1) / TICK_RATE;
+        self.pending += elapsed;
+        let due = (self.pending.as_nanos() / tick.as_nanos()) as u32;
+        if due > MAX_TICKS_PER_FRAME {
+            self.pending = Duration::ZERO;
+            return MAX_TICKS_PER_FRAME;
+        }
+        self.pending -= tick * due;
+        due
+    }
+}
@@ -0,0 +1,156 @@
+use crate::console::Command;
+use crate::items::MAX_AMMO;
+use crate::map::{MAP_HEIGHT, MAP_WIDTH};
+use crate::player::{Key, Weapon, MAX_HEALTH};
+use crate::App;
+
+/// Every console command. Cvars are not listed here; typing a cvar's name shows or sets it.
+pub fn registry() -> Vec<Command<App>> {
+    vec![
+        Command { name: "help", usage: "help", run: help },
+        Command { name: "cvarlist", usage: "cvarlist", run: cvarlist },
+        Command { name: "reset", usage: "reset <cvar>", run: reset },
+        Command { name: "writeconfig", usage: "writeconfig", run: writeconfig },
+        Command { name: "clear", usage: "clear", run: clear },
+        Command { name: "noclip", usage: "noclip", run: noclip },
+        Command { name: "god", usage: "god", run: god },
+        Command { name: "give", usage: "give all|health|ammo|keys|weapons", run: give },
+        Command { name: "map", usage: "map <name>", run: map },
+        Command { name: "tp", usage: "tp <x> <y> [angle]", run: tp },
+        Command { name: "kill", usage: "kill all", run: kill },
+        Command { name: "record", usage: "record <name>", run: record },
+        Command { name: "playdemo", usage: "playdemo <name>", run: playdemo },
+        Command { name: "stopdemo", usage: "stopdemo", run: stopdemo },
+    ]
+}
+
+fn help(app: &mut App, _: &[&str]) -> Result<String, String> {
+    let mut out = String::from("commands:\n");
+    for c in app.console.commands() {
+        out += &format!("  {}\n", c.usage);
+    }
+    out += "type a cvar name to show it, or <cvar> <value> to set it; see cvarlist";
+    Ok(out)
+}
+
+fn cvarlist(app: &mut App, _: &[&str]) -> Result<String, String> {
+    Ok(app.cvars.iter().map(|v| format!("{} {} - {}", v.name, v.value, v.help)).collect::<Vec<_>>().join("\n"))
+}
+
+fn reset(app: &mut App, args: &[&str]) -> Result<String, String> {
+    let [name] = args else { return Err("usage: reset <cvar>".to_string()) };
+    let value = app.cvars.reset(name)?;
+    app.cvars.save()?;
+    Ok(format!("{name} {value}"))
+}
+
+fn writeconfig(app: &mut App, _: &[&str]) -> Result<String, String> {
+    app.cvars.save()?;
+    Ok(format!("wrote {}", crate::cvar::CONFIG_FILE))
+}
+
+fn clear(app: &mut App, _: &[&str]) -> Result<String, String> {
+    app.console.clear();
+    Ok(String::new())
+}
+
+fn on_off(v: bool) -> &'static str {
+    if v { "ON" } else { "OFF" }
+}
+
+// The cheats change the game behind a demo's back, so each ends any demo first; a recording is
+// saved up to that point and playback hands the game to the player.
+
+fn noclip(app: &mut App, _: &[&str]) -> Result<String, String> {
+    app.stop_demo();
+    app.game.world.player.noclip = !app.game.world.player.noclip;
+    Ok(format!("noclip {}", on_off(app.game.world.player.noclip)))
+}
+
+fn god(app: &mut App, _: &[&str]) -> Result<String, String> {
+    app.stop_demo();
+    app.game.world.player.god = !app.game.world.player.god;
+    Ok(format!("god mode {}", on_off(app.game.world.player.god)))
+}
+
+fn give(app: &mut App, args: &[&str]) -> Result<String, String> {
+    let [what] = args else { return Err("usage: give all|health|ammo|keys|weapons".to_string()) };
+    app.stop_demo();
+    let p = &mut app.game.world.player;
+    let all = *what == "all";
+    let mut known = all;
+    if all || *what == "health" {
+        p.health = MAX_HEALTH;
+        known = true;
+    }
+    if all || *what == "ammo" {
+        p.ammo = MAX_AMMO;
+        known = true;
+    }
+    if all || *what == "keys" {
+        p.give_key(Key::Gold);
+        p.give_key(Key::Silver);
+        known = true;
+    }
+    if all || *what == "weapons" {
+        p.weapon = Weapon::Chaingun;
+        known = true;
+    }
+    if !known {
+        return Err(format!("can't give {what}"));
+    }
+    Ok(format!("gave {what}"))
+}
+
+fn map(app: &mut App, args: &[&str]) -> Result<String, String> {
+    let [name] = args else { return Err("usage: map <name>".to_string()) };
+    app.change_map(name)?;
+    Ok(format!("loaded {name}"))
+}
+
+fn tp(app: &mut App, args: &[&str]) -> Result<String, String> {
+    let usage = || "usage: tp <x> <y> [angle]".to_string();
+    let nums: Vec<f32> = args.iter().map(|a| a.parse().ok()).collect::<Option<_>>().ok_or_else(usage)?;
+    let (x, y, angle) = match nums.as_slice() {
+        [x, y] => (*x, *y, None),
+        [x, y, a] => (*x, *y, Some(a.to_radians())),
+        _ => return Err(usage()),
+    };
+    if !(0.0..MAP_WIDTH as f32).contains(&x) || !(0.0..MAP_HEIGHT as f32).contains(&y) {
+        return Err(format!("{x} {y} is outside the map"));
+    }
+    app.stop_demo();
+    app.game.world.player.x = x;
+    app.game.world.player.y = y;
+    if let Some(a) = angle {
+        app.game.world.player.angle = a;
+    }
+    Ok(format!("teleported to {x} {y}"))
+}
+
+fn kill(app: &mut App, args: &[&str]) -> Result<String, String> {
+    let ["all"] = args else { return Err("usage: kill all".to_string()) };
+    app.stop_demo();
+    let before = app.game.world.kills();
+    let events = app.game.world.kill_all();
+    app.present(events);
+    let n = app.game.world.kills() - before;
+    Ok(format!("killed {n} enemies"))
+}
+
+fn record(app: &mut App, args: &[&str]) -> Result<String, String> {
+    let [name] = args else { return Err("usage: record <name>".to_string()) };
+    app.record_demo(name)?;
+    Ok(format!("recording {name}; stopdemo or finishing the level saves it"))
+}
+
+fn playdemo(app: &mut App, args: &[&str]) -> Result<String, String> {
+    let [name] = args else { return Err("usage: playdemo <name>".to_string()) };
+    app.play_demo(name)?;
+    Ok(format!("playing {name}"))
+}
+
+fn stopdemo(app: &mut App, _: &[&str]) -> Result<String, String> {
+    app.stop_demo();
+    Ok("demo stopped".to_string())
+}
@@ -0,0 +1,119 @@
+use winit::keyboard::KeyCode;
+
+/// Lines of output kept for scrollback.
+const MAX_LINES: usize = 200;
+const MAX_HISTORY: usize = 50;
+
+/// Command handler: gets the game and the arguments after the command name,
+/// returns the text to print.
+pub type CommandFn<T> = fn(&mut T, &[&str]) -> Result<String, String>;
+
+pub struct Command<T> {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub run: CommandFn<T>,
+}
+
+/// Quake-style drop-down console: an input line, scrollback and a registry of commands
+/// run against a `T`.
+pub struct Console<T> {
+    pub open: bool,
+    commands: Vec<Command<T>>,
+    input: String,
+    lines: Vec<String>,
+    history: Vec<String>,
+    /// Position while browsing history with the arrow keys; `None` when editing a fresh line.
+    history_pos: Option<usize>,
+}
+
+impl<T> Console<T> {
+    pub fn new(commands: Vec<Command<T>>) -> Self {
+        Self {
+            open: false,
+            commands,
+            input: String::new(),
+            lines: Vec::new(),
+            history: Vec::new(),
+            history_pos: None,
+        }
+    }
+
+    pub fn commands(&self) -> impl Iterator<Item = &Command<T>> {
+        self.commands.iter()
+    }
+
+    pub fn find(&self, name: &str) -> Option<CommandFn<T>> {
+        self.commands.iter().find(|c| c.name == name).map(|c| c.run)
+    }
+
+    pub fn view(&self) -> Option<ConsoleView<'_>> {
+        self.open.then(|| ConsoleView { lines: &self.lines, input: &self.input })
+    }
+
+    pub fn print(&mut self, text: &str) {
+        for line in text.lines() {
+            self.lines.push(line.to_string());
+        }
+        if self.lines.len() > MAX_LINES {
+            self.lines.drain(..self.lines.len() - MAX_LINES);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    /// Appends typed text, dropping anything the bitmap font cannot draw.
+    pub fn type_text(&mut self, text: &str) {
+        self.input.extend(text.chars().filter(|c| (' '..='~').contains(c) && *c != '`'));
+    }
+
+    /// Handles editing keys. Returns a submitted line when Enter is pressed.
+    pub fn handle_key(&mut self, key: KeyCode) -> Option<String> {
+        match key {
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Escape => self.open = false,
+            KeyCode::ArrowUp if !self.history.is_empty() => {
+                let pos = self.history_pos.map_or(self.history.len() - 1, |p| p.saturating_sub(1));
+                self.history_pos = Some(pos);
+                self.input = self.history[pos].clone();
+            }
+            KeyCode::ArrowDown => {
+                if let Some(pos) = self.history_pos {
+                    if pos + 1 < self.history.len() {
+                        self.history_pos = Some(pos + 1);
+                        self.input = self.history[pos + 1].clone();
+                    } else {
+                        self.history_pos = None;
+                        self.input.clear();
+                    }
+                }
+            }
+            KeyCode::Enter | KeyCode::NumpadEnter => {
+                let line = std::mem::take(&mut self.input);
+                self.history_pos = None;
+                self.print(&format!("] {line}"));
+                if line.trim().is_empty() {
+                    return None;
+                }
+                if self.history.last() != Some(&line) {
+                    self.history.push(line.clone());
+                    if self.history.len() > MAX_HISTORY {
+                        self.history.remove(0);
+                    }
+                }
+                return Some(line);
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+/// What the renderer needs to draw an open console.
+pub struct ConsoleView<'a> {
+    pub lines: &'a [String],
+    pub input: &'a str,
+}
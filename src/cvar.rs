@@ -0,0 +1,187 @@
+use std::fmt;
+
+use crate::map::MAP_WIDTH;
+use crate::minimap::MinimapConfig;
+use crate::player::{MOVE_SPEED, ROT_SPEED};
+use crate::raycaster::{FOV, NUM_RAYS, SCREEN_H, SCREEN_W};
+use crate::storage;
+
+/// File cvars are persisted to, one `name value` line each.
+pub const CONFIG_FILE: &str = "config.cfg";
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CvarValue {
+    Bool(bool),
+    Int(i32),
+    Float(f32),
+    /// Linear RGB, written as three numbers.
+    Color([f32; 3]),
+}
+
+impl CvarValue {
+    /// Parses `text` as a value of the same type as `self`.
+    fn parse_like(self, text: &str) -> Option<CvarValue> {
+        Some(match self {
+            CvarValue::Bool(_) => CvarValue::Bool(match text {
+                "1" | "on" | "true" => true,
+                "0" | "off" | "false" => false,
+                _ => return None,
+            }),
+            CvarValue::Int(_) => CvarValue::Int(text.parse().ok()?),
+            CvarValue::Float(_) => CvarValue::Float(text.parse().ok()?),
+            CvarValue::Color(_) => {
+                let parts: Vec<f32> = text.split_whitespace().map(|p| p.parse().ok()).collect::<Option<_>>()?;
+                let [r, g, b] = parts.as_slice() else { return None };
+                CvarValue::Color([*r, *g, *b])
+            }
+        })
+    }
+
+    fn clamp(self, min: f32, max: f32) -> CvarValue {
+        match self {
+            CvarValue::Int(v) => CvarValue::Int(v.clamp(min as i32, max as i32)),
+            CvarValue::Float(v) => CvarValue::Float(v.clamp(min, max)),
+            CvarValue::Color(c) => CvarValue::Color(c.map(|v| v.clamp(min, max))),
+            CvarValue::Bool(_) => self,
+        }
+    }
+}
+
+impl fmt::Display for CvarValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CvarValue::Bool(v) => write!(f, "{}", *v as u8),
+            CvarValue::Int(v) => write!(f, "{v}"),
+            CvarValue::Float(v) => write!(f, "{v}"),
+            CvarValue::Color([r, g, b]) => write!(f, "{r} {g} {b}"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Cvar {
+    pub name: &'static str,
+    pub help: &'static str,
+    pub value: CvarValue,
+    pub default: CvarValue,
+    /// Numeric bounds; colour channels are clamped to them too.
+    pub min: f32,
+    pub max: f32,
+}
+
+impl Cvar {
+    const fn new(name: &'static str, value: CvarValue, min: f32, max: f32, help: &'static str) -> Self {
+        Self { name, help, value, default: value, min, max }
+    }
+}
+
+/// Typed engine settings, tweakable from the console and persisted to `CONFIG_FILE`.
+pub struct Cvars {
+    vars: Vec<Cvar>,
+}
+
+impl Default for Cvars {
+    fn default() -> Self {
+        let minimap = MinimapConfig::default();
+        Self {
+            vars: vec![
+                Cvar::new("fov", CvarValue::Float(FOV.to_degrees()), 30.0, 120.0, "field of view in degrees"),
+                Cvar::new("r_resolution", CvarValue::Int(NUM_RAYS as i32), 40.0, 1920.0, "rays cast per frame"),
+                Cvar::new("move_speed", CvarValue::Float(MOVE_SPEED), 0.0, 0.5, "tiles walked per tick"),
+                Cvar::new("rot_speed", CvarValue::Float(ROT_SPEED), 0.0, 0.5, "radians turned per tick"),
+                Cvar::new("r_ceiling_color", CvarValue::Color([0.15, 0.15, 0.25]), 0.0, 1.0, "ceiling colour"),
+                Cvar::new("r_floor_color", CvarValue::Color([0.25, 0.20, 0.15]), 0.0, 1.0, "floor colour"),
+                Cvar::new("r_minimap_x", CvarValue::Float(minimap.x), 0.0, SCREEN_W as f32, "minimap left edge in pixels"),
+                Cvar::new("r_minimap_y", CvarValue::Float(minimap.y), 0.0, SCREEN_H as f32, "minimap top edge in pixels"),
+                Cvar::new("r_minimap_size", CvarValue::Float(minimap.tile_px), 1.0, 32.0, "minimap tile size in pixels"),
+                Cvar::new("r_minimap_tiles", CvarValue::Int(minimap.view_tiles as i32), 4.0, MAP_WIDTH as f32, "minimap width in tiles"),
+                Cvar::new("r_minimap_opacity", CvarValue::Float(minimap.opacity), 0.0, 1.0, "minimap opacity"),
+            ],
+        }
+    }
+}
+
+impl Cvars {
+    /// Defaults overlaid with whatever the config file sets. Bad lines are skipped.
+    pub fn load() -> Self {
+        let mut cvars = Self::default();
+        if let Ok(text) = storage::read(CONFIG_FILE) {
+            for err in cvars.apply_config(&text) {
+                log::warn!("{CONFIG_FILE}: {err}");
+            }
+        }
+        cvars
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        storage::write(CONFIG_FILE, &self.to_config())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Cvar> {
+        self.vars.iter()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Cvar> {
+        self.vars.iter().find(|v| v.name == name)
+    }
+
+    /// Parses and stores a new value, clamped to the cvar's range.
+    pub fn set(&mut self, name: &str, text: &str) -> Result<CvarValue, String> {
+        let var = self.vars.iter_mut().find(|v| v.name == name).ok_or_else(|| format!("unknown cvar {name}"))?;
+        let value = var.value.parse_like(text).ok_or_else(|| format!("bad value for {name}: {text}"))?;
+        var.value = value.clamp(var.min, var.max);
+        Ok(var.value)
+    }
+
+    pub fn reset(&mut self, name: &str) -> Result<CvarValue, String> {
+        let var = self.vars.iter_mut().find(|v| v.name == name).ok_or_else(|| format!("unknown cvar {name}"))?;
+        var.value = var.default;
+        Ok(var.value)
+    }
+
+    pub fn float(&self, name: &str) -> f32 {
+        match self.get(name).map(|v| v.value) {
+            Some(CvarValue::Float(v)) => v,
+            _ => panic!("{name} is not a float cvar"),
+        }
+    }
+
+    pub fn int(&self, name: &str) -> i32 {
+        match self.get(name).map(|v| v.value) {
+            Some(CvarValue::Int(v)) => v,
+            _ => panic!("{name} is not an int cvar"),
+        }
+    }
+
+    pub fn color(&self, name: &str) -> [f32; 4] {
+        match self.get(name).map(|v| v.value) {
+            Some(CvarValue::Color([r, g, b])) => [r, g, b, 1.0],
+            _ => panic!("{name} is not a colour cvar"),
+        }
+    }
+
+    /// Only cvars changed from their defaults are written.
+    pub fn to_config(&self) -> String {
+        let mut out = String::from("# Written by the console; one `cvar value` per line.\n");
+        for var in self.vars.iter().filter(|v| v.value != v.default) {
+            out += &format!("{} {}\n", var.name, var.value);
+        }
+        out
+    }
+
+    /// Applies `name value` lines. Returns a message for every line that could not be applied.
+    pub fn apply_config(&mut self, text: &str) -> Vec<String> {
+        let mut errors = Vec::new();
+        for line in text.lines() {
+            if line.starts_with('#') || line.trim().is_empty() {
+                continue;
+            }
+            let line = line.trim();
+            let (name, value) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            if let Err(e) = self.set(name, value.trim()) {
+                errors.push(e);
+            }
+        }
+        errors
+    }
+}
@@ -0,0 +1,169 @@
+use crate::difficulty::Difficulty;
+use crate::game::{self, Game};
+use crate::rng::Rng;
+use crate::storage;
+use crate::world::{Event, TickInput};
+
+/// Bumped whenever the demo layout or the simulation changes; older demos would desync anyway.
+pub const DEMO_VERSION: u32 = 1;
+const DEMO_MAGIC: &str = "WOLF3D-DEMO";
+/// Directory demo files are kept in.
+pub const DEMO_DIR: &str = "demos";
+/// Checksums per `checks` line of a demo file.
+const CHECKS_PER_LINE: usize = 64;
+
+/// A recorded run: where it starts, the input of every tick, and a checksum of the game state
+/// after each tick so playback can tell exactly where it went out of step.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Demo {
+    pub map: String,
+    pub difficulty: Difficulty,
+    pub seed: u64,
+    /// Tiles walked per tick.
+    pub move_speed: f32,
+    /// Radians turned per tick.
+    pub rot_speed: f32,
+    pub inputs: Vec<TickInput>,
+    /// `checksum` after each tick. Ticks past the end go unchecked.
+    pub checksums: Vec<u16>,
+}
+
+impl Demo {
+    /// An empty demo starting on `map`.
+    pub fn new(map: &str, difficulty: Difficulty, seed: u64, move_speed: f32, rot_speed: f32) -> Self {
+        Self { map: map.to_string(), difficulty, seed, move_speed, rot_speed, inputs: Vec::new(), checksums: Vec::new() }
+    }
+
+    /// Starts `game` over on the demo's map with a fresh player, as the recording did.
+    pub fn start(&self, game: &mut Game) -> Result<(), String> {
+        game.new_game(self.difficulty)?;
+        game.change_map(&self.map)?;
+        let world = &mut game.world;
+        world.rng = Rng::new(self.seed);
+        world.move_speed = self.move_speed;
+        world.rot_speed = self.rot_speed;
+        Ok(())
+    }
+
+    /// Appends a tick `game` has just run with `input`.
+    pub fn record(&mut self, input: TickInput, game: &Game) {
+        self.inputs.push(input);
+        self.checksums.push(checksum(game));
+    }
+
+    /// Serialises to the line-based demo format. Inputs are run-length coded as in input scripts.
+    pub fn to_text(&self) -> String {
+        let mut out = format!("{DEMO_MAGIC} {DEMO_VERSION}\n");
+        out += &format!("map {}\n", self.map);
+        out += &format!("difficulty {}\n", self.difficulty.token());
+        out += &format!("seed {}\n", self.seed);
+        out += &format!("speed {} {}\n", self.move_speed, self.rot_speed);
+        for run in self.inputs.chunk_by(|a, b| a == b) {
+            out += &format!("input {}\n", game::format_run(run.len(), run[0]));
+        }
+        for checks in self.checksums.chunks(CHECKS_PER_LINE) {
+            let hex: String = checks.iter().map(|c| format!("{c:04x}")).collect();
+            out += &format!("checks {hex}\n");
+        }
+        out
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines();
+        let header = lines.next().unwrap_or_default();
+        let version = header
+            .strip_prefix(DEMO_MAGIC)
+            .and_then(|v| v.trim().parse::<u32>().ok())
+            .ok_or("not a demo file")?;
+        if version != DEMO_VERSION {
+            return Err(format!("unsupported demo version {version} (expected {DEMO_VERSION})"));
+        }
+
+        let mut demo = Demo::new("", Difficulty::Medium, 0, 0.0, 0.0);
+        let mut have_speed = false;
+        for (n, line) in lines.enumerate() {
+            let bad = || format!("bad demo record on line {}: {line}", n + 2);
+            let mut t = line.split_whitespace();
+            let Some(key) = t.next() else { continue };
+            let rest: Vec<&str> = t.collect();
+            match (key, rest.as_slice()) {
+                ("map", [name]) => demo.map = name.to_string(),
+                ("difficulty", [d]) => demo.difficulty = Difficulty::from_token(d).ok_or_else(bad)?,
+                ("seed", [seed]) => demo.seed = seed.parse().map_err(|_| bad())?,
+                ("speed", [move_speed, rot_speed]) => {
+                    demo.move_speed = move_speed.parse().map_err(|_| bad())?;
+                    demo.rot_speed = rot_speed.parse().map_err(|_| bad())?;
+                    have_speed = true;
+                }
+                ("input", _) => {
+                    let (count, input) = game::parse_run(&rest.join(" ")).ok_or_else(bad)?;
+                    demo.inputs.extend(std::iter::repeat_n(input, count));
+                }
+                ("checks", [hex]) if hex.len() % 4 == 0 && hex.is_ascii() => {
+                    for i in (0..hex.len()).step_by(4) {
+                        demo.checksums.push(u16::from_str_radix(&hex[i..i + 4], 16).map_err(|_| bad())?);
+                    }
+                }
+                _ => return Err(bad()),
+            }
+        }
+
+        if demo.map.is_empty() || !have_speed {
+            return Err("demo is missing its map or speed record".to_string());
+        }
+        Ok(demo)
+    }
+}
+
+/// The state hash folded to 16 bits, enough to catch a desync within a tick or two.
+pub fn checksum(game: &Game) -> u16 {
+    let h = game.state_hash();
+    (h ^ h >> 16 ^ h >> 32 ^ h >> 48) as u16
+}
+
+/// Plays a demo back into a game, checking the state after every tick.
+pub struct Playback {
+    pub demo: Demo,
+    /// Ticks played so far.
+    pub tick: usize,
+    /// First tick after which the state differed from the recording, counting from 1.
+    pub desync: Option<usize>,
+}
+
+impl Playback {
+    /// Starts `game` where the demo starts.
+    pub fn start(demo: Demo, game: &mut Game) -> Result<Self, String> {
+        demo.start(game)?;
+        Ok(Self { demo, tick: 0, desync: None })
+    }
+
+    /// Runs the next recorded tick, or returns `None` once the demo is over.
+    pub fn tick(&mut self, game: &mut Game) -> Option<Vec<Event>> {
+        let input = *self.demo.inputs.get(self.tick)?;
+        let events = game.tick(&input);
+        let expected = self.demo.checksums.get(self.tick).copied();
+        self.tick += 1;
+        if self.desync.is_none() && expected.is_some_and(|c| c != checksum(game)) {
+            self.desync = Some(self.tick);
+        }
+        Some(events)
+    }
+
+    pub fn finished(&self) -> bool {
+        self.tick >= self.demo.inputs.len()
+    }
+}
+
+fn slot_path(name: &str) -> Result<String, String> {
+    storage::slot_path(DEMO_DIR, name, "dmo")
+}
+
+/// Writes `demo` to the named slot: `demos/<name>.dmo` on native, localStorage on the web.
+pub fn write_slot(name: &str, demo: &Demo) -> Result<(), String> {
+    storage::write(&slot_path(name)?, &demo.to_text())
+}
+
+pub fn read_slot(name: &str) -> Result<Demo, String> {
+    let text = storage::read(&slot_path(name)?).map_err(|e| format!("no demo named {name}: {e}"))?;
+    Demo::parse(&text)
+}
@@ -0,0 +1,79 @@
+/// Skill level picked when starting a new game.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Difficulty {
+    Baby,
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [Difficulty::Baby, Difficulty::Easy, Difficulty::Medium, Difficulty::Hard];
+
+    /// The original game's menu wording.
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Baby => "CAN I PLAY, DADDY?",
+            Difficulty::Easy => "DON'T HURT ME.",
+            Difficulty::Medium => "BRING 'EM ON!",
+            Difficulty::Hard => "I AM DEATH INCARNATE!",
+        }
+    }
+
+    /// Name used in save files.
+    pub fn token(self) -> &'static str {
+        match self {
+            Difficulty::Baby => "baby",
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+        }
+    }
+
+    pub fn from_token(token: &str) -> Option<Self> {
+        Difficulty::ALL.into_iter().find(|d| d.token() == token)
+    }
+
+    /// Actor-layer suffix giving the lowest difficulty a spawn appears on, e.g. `2m` for an SS
+    /// that only shows up on medium and hard.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        Some(match tag {
+            "" | "b" => Difficulty::Baby,
+            "e" => Difficulty::Easy,
+            "m" => Difficulty::Medium,
+            "h" => Difficulty::Hard,
+            _ => return None,
+        })
+    }
+
+    /// Multiplier on damage the player takes.
+    pub fn damage_scale(self) -> f32 {
+        match self {
+            Difficulty::Baby => 0.25,
+            Difficulty::Easy => 0.5,
+            Difficulty::Medium => 1.0,
+            Difficulty::Hard => 1.25,
+        }
+    }
+
+    /// Multiplier on ammo picked up.
+    pub fn ammo_scale(self) -> f32 {
+        match self {
+            Difficulty::Baby => 2.0,
+            Difficulty::Easy => 1.5,
+            Difficulty::Medium | Difficulty::Hard => 1.0,
+        }
+    }
+
+    /// Scales a hit on the player, never below 1 so every hit still hurts.
+    pub fn scale_damage(self, amount: i32) -> i32 {
+        if amount <= 0 {
+            return amount;
+        }
+        ((amount as f32 * self.damage_scale()).round() as i32).max(1)
+    }
+
+    pub fn scale_ammo(self, amount: u32) -> u32 {
+        (amount as f32 * self.ammo_scale()).round() as u32
+    }
+}
@@ -0,0 +1,80 @@
+use crate::player::Key;
+
+/// Open fraction gained or lost per tick while a door slides.
+pub const DOOR_SPEED: f32 = 1.0 / 32.0;
+/// Ticks a fully open door waits before closing again.
+pub const DOOR_OPEN_TICKS: u32 = 180;
+/// Open fraction above which actors can pass through.
+pub const DOOR_PASSABLE: f32 = 0.9;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DoorKind {
+    Normal,
+    GoldLocked,
+    SilverLocked,
+    Elevator,
+}
+
+impl DoorKind {
+    pub fn required_key(self) -> Option<Key> {
+        match self {
+            DoorKind::GoldLocked => Some(Key::Gold),
+            DoorKind::SilverLocked => Some(Key::Silver),
+            DoorKind::Normal | DoorKind::Elevator => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DoorAction {
+    Closed,
+    Opening,
+    Open { ticks: u32 },
+    Closing,
+}
+
+#[derive(Clone, Debug)]
+pub struct Door {
+    pub x: i32,
+    pub y: i32,
+    pub kind: DoorKind,
+    /// 0.0 is fully closed, 1.0 fully open.
+    pub open: f32,
+    pub action: DoorAction,
+}
+
+impl Door {
+    pub fn new(x: i32, y: i32, kind: DoorKind) -> Self {
+        Self { x, y, kind, open: 0.0, action: DoorAction::Closed }
+    }
+
+    /// Starts the door opening, or closing if it is already open.
+    pub fn activate(&mut self) {
+        self.action = match self.action {
+            DoorAction::Closed | DoorAction::Closing => DoorAction::Opening,
+            DoorAction::Opening | DoorAction::Open { .. } => DoorAction::Closing,
+        };
+    }
+
+    /// Advances the slide. `blocked` keeps an open door from closing on whoever stands in it.
+    pub fn update(&mut self, blocked: bool) {
+        self.action = match self.action {
+            DoorAction::Closed => DoorAction::Closed,
+            DoorAction::Opening => {
+                self.open = (self.open + DOOR_SPEED).min(1.0);
+                if self.open >= 1.0 { DoorAction::Open { ticks: 0 } } else { DoorAction::Opening }
+            }
+            DoorAction::Open { ticks } => {
+                if ticks >= DOOR_OPEN_TICKS && !blocked { DoorAction::Closing } else { DoorAction::Open { ticks: ticks + 1 } }
+            }
+            DoorAction::Closing => {
+                if blocked {
+                    DoorAction::Opening
+                } else {
+                    self.open = (self.open - DOOR_SPEED).max(0.0);
+                    if self.open <= 0.0 { DoorAction::Closed } else { DoorAction::Closing }
+                }
+            }
+        };
+    }
+}
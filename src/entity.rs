@@ -0,0 +1,152 @@
+use std::ops::{Index, IndexMut};
+
+/// Handle to an entity in an `Entities` store. It stays valid until the entity is despawned,
+/// and never refers to a different entity that later reuses the slot.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct EntityId {
+    index: u32,
+    generation: u32,
+}
+
+impl EntityId {
+    pub fn index(self) -> usize {
+        self.index as usize
+    }
+
+    pub fn generation(self) -> u32 {
+        self.generation
+    }
+
+    /// Rebuilds an id from `index` and `generation`, as written by saves and demos.
+    pub fn from_parts(index: usize, generation: u32) -> Self {
+        Self { index: index as u32, generation }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+/// Slot map of one kind of entity. Spawning reuses the slots of despawned entities, bumping
+/// the slot's generation so stale ids stop resolving. Iteration runs in slot order.
+#[derive(Clone, Debug)]
+pub struct Entities<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+    len: usize,
+}
+
+impl<T> Default for Entities<T> {
+    fn default() -> Self {
+        Self { slots: Vec::new(), free: Vec::new(), len: 0 }
+    }
+}
+
+impl<T> Entities<T> {
+    pub fn spawn(&mut self, value: T) -> EntityId {
+        self.len += 1;
+        if let Some(index) = self.free.pop() {
+            let slot = &mut self.slots[index as usize];
+            slot.value = Some(value);
+            return EntityId { index, generation: slot.generation };
+        }
+        self.slots.push(Slot { generation: 0, value: Some(value) });
+        EntityId { index: self.slots.len() as u32 - 1, generation: 0 }
+    }
+
+    /// Removes the entity, returning it; `None` if `id` was already despawned.
+    pub fn despawn(&mut self, id: EntityId) -> Option<T> {
+        let slot = self.slots.get_mut(id.index())?;
+        if slot.generation != id.generation {
+            return None;
+        }
+        let value = slot.value.take()?;
+        slot.generation += 1;
+        self.free.push(id.index);
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Despawns every entity `keep` returns false for.
+    pub fn retain(&mut self, mut keep: impl FnMut(EntityId, &mut T) -> bool) {
+        for id in self.ids() {
+            if !keep(id, &mut self[id]) {
+                self.despawn(id);
+            }
+        }
+    }
+
+    pub fn get(&self, id: EntityId) -> Option<&T> {
+        self.slots.get(id.index()).filter(|s| s.generation == id.generation)?.value.as_ref()
+    }
+
+    pub fn get_mut(&mut self, id: EntityId) -> Option<&mut T> {
+        self.slots.get_mut(id.index()).filter(|s| s.generation == id.generation)?.value.as_mut()
+    }
+
+    pub fn contains(&self, id: EntityId) -> bool {
+        self.get(id).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Ids of every live entity, so callers can mutate the store while walking them.
+    pub fn ids(&self) -> Vec<EntityId> {
+        self.iter().map(|(id, _)| id).collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (EntityId, &T)> {
+        self.slots.iter().enumerate().filter_map(|(i, s)| {
+            let id = EntityId { index: i as u32, generation: s.generation };
+            s.value.as_ref().map(|v| (id, v))
+        })
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (EntityId, &mut T)> {
+        self.slots.iter_mut().enumerate().filter_map(|(i, s)| {
+            let id = EntityId { index: i as u32, generation: s.generation };
+            s.value.as_mut().map(|v| (id, v))
+        })
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.slots.iter().filter_map(|s| s.value.as_ref())
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.slots.iter_mut().filter_map(|s| s.value.as_mut())
+    }
+}
+
+impl<T> FromIterator<T> for Entities<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut entities = Self::default();
+        for value in iter {
+            entities.spawn(value);
+        }
+        entities
+    }
+}
+
+/// Panics if `id` has been despawned.
+impl<T> Index<EntityId> for Entities<T> {
+    type Output = T;
+
+    fn index(&self, id: EntityId) -> &T {
+        self.get(id).unwrap_or_else(|| panic!("no entity {id:?}"))
+    }
+}
+
+impl<T> IndexMut<EntityId> for Entities<T> {
+    fn index_mut(&mut self, id: EntityId) -> &mut T {
+        self.get_mut(id).unwrap_or_else(|| panic!("no entity {id:?}"))
+    }
+}
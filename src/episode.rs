@@ -0,0 +1,137 @@
+use crate::assets;
+
+/// Simulation ticks per second, paced by `clock::TickClock` whatever the display's refresh rate.
+pub const TICK_RATE: u32 = 60;
+
+#[derive(Clone, Debug)]
+pub struct EpisodeMap {
+    pub name: String,
+    pub par_secs: u32,
+    /// Map the secret exit leads to.
+    pub secret: Option<String>,
+    /// Only reachable through a secret exit; skipped by normal progression.
+    pub hidden: bool,
+}
+
+/// Ordered list of maps making up an episode, read from a manifest such as `maps/episode1.txt`.
+#[derive(Clone, Debug)]
+pub struct Episode {
+    pub maps: Vec<EpisodeMap>,
+}
+
+impl Episode {
+    pub fn load(name: &str) -> Result<Self, String> {
+        let text = assets::load(name).ok_or_else(|| format!("episode manifest {name} not found"))?;
+        let episode = Self::parse(&text);
+        if episode.maps.is_empty() {
+            return Err(format!("episode manifest {name} lists no maps"));
+        }
+        Ok(episode)
+    }
+
+    fn parse(content: &str) -> Self {
+        let mut maps = Vec::new();
+        for line in content.lines() {
+            if line.starts_with('#') || line.trim().is_empty() {
+                continue;
+            }
+            let mut tokens = line.split_whitespace();
+            let Some(name) = tokens.next() else { continue };
+            let mut map = EpisodeMap { name: name.to_string(), par_secs: 0, secret: None, hidden: false };
+            for token in tokens {
+                if token == "hidden" {
+                    map.hidden = true;
+                } else if let Some(secret) = token.strip_prefix("secret=") {
+                    map.secret = Some(secret.to_string());
+                } else if let Ok(par) = token.parse() {
+                    map.par_secs = par;
+                }
+            }
+            maps.push(map);
+        }
+        Self { maps }
+    }
+
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.maps.iter().position(|m| m.name == name)
+    }
+
+    /// Floor number shown for map `index`. Regular maps count from 1 in play order; hidden maps
+    /// are numbered after the last of them, so visiting a secret level never shifts the others.
+    pub fn floor(&self, index: usize) -> u32 {
+        let hidden = self.maps[index].hidden;
+        let before = self.maps[..=index].iter().filter(|m| m.hidden == hidden).count();
+        let regular = if hidden { self.maps.iter().filter(|m| !m.hidden).count() } else { 0 };
+        (regular + before) as u32
+    }
+
+    /// First non-hidden map after `index`, or `None` at the end of the episode.
+    pub fn next_after(&self, index: usize) -> Option<usize> {
+        (index + 1..self.maps.len()).find(|&i| !self.maps[i].hidden)
+    }
+}
+
+/// Where the player is in the episode.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Progress {
+    pub index: usize,
+    /// Map to resume from after a secret level.
+    pub return_to: Option<usize>,
+}
+
+impl Progress {
+    pub fn start() -> Self {
+        Self { index: 0, return_to: None }
+    }
+
+    /// Advances past the current map. Returns false once the episode is finished.
+    pub fn advance(&mut self, episode: &Episode, secret_exit: bool) -> bool {
+        let current = &episode.maps[self.index];
+        if secret_exit {
+            if let Some(target) = current.secret.as_deref().and_then(|s| episode.index_of(s)) {
+                self.return_to = Some(self.index);
+                self.index = target;
+                return true;
+            }
+        }
+        let from = self.return_to.take().unwrap_or(self.index);
+        match episode.next_after(from) {
+            Some(next) => {
+                self.index = next;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// End-of-level tallies shown on the intermission screen.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LevelStats {
+    pub floor: u32,
+    pub kills: usize,
+    pub kills_total: usize,
+    pub secrets: usize,
+    pub secrets_total: usize,
+    pub treasure: usize,
+    pub treasure_total: usize,
+    pub ticks: u32,
+    pub par_secs: u32,
+    /// True once the last map of the episode is done.
+    pub episode_done: bool,
+}
+
+impl LevelStats {
+    pub fn ratio(found: usize, total: usize) -> u32 {
+        (found * 100).checked_div(total).map_or(100, |r| r as u32)
+    }
+
+    pub fn seconds(&self) -> u32 {
+        self.ticks / TICK_RATE
+    }
+}
+
+/// Formats seconds as `m:ss`.
+pub fn format_time(secs: u32) -> String {
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
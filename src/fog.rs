@@ -0,0 +1,71 @@
+/// Number of light steps in the diminishing colormap, as in Doom's COLORMAP lump.
+pub const NUM_COLORMAPS: usize = 32;
+
+/// How far (in tiles) one colormap step reaches when diminishing light.
+const DIMINISH_STEP: f32 = 0.5;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum FogMode {
+    None,
+    Linear { start: f32, end: f32 },
+    Exponential { density: f32 },
+    /// Doom-style light diminishing: the colour steps down a colormap with distance.
+    Diminish { light: f32 },
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Fog {
+    pub mode: FogMode,
+    pub color: [f32; 3],
+}
+
+impl Default for Fog {
+    fn default() -> Self {
+        Self { mode: FogMode::None, color: [0.0, 0.0, 0.0] }
+    }
+}
+
+impl Fog {
+    /// Fraction of the fog colour mixed in at `distance`, from 0.0 (clear) to 1.0 (fully fogged).
+    pub fn factor(&self, distance: f32) -> f32 {
+        match self.mode {
+            FogMode::None | FogMode::Diminish { .. } => 0.0,
+            FogMode::Linear { start, end } => {
+                if end <= start {
+                    return if distance >= end { 1.0 } else { 0.0 };
+                }
+                ((distance - start) / (end - start)).clamp(0.0, 1.0)
+            }
+            FogMode::Exponential { density } => 1.0 - (-density * distance).exp(),
+        }
+    }
+
+    /// Brightness picked from the colormap at `distance`. Always 1.0 outside `Diminish`.
+    pub fn colormap_scale(&self, distance: f32) -> f32 {
+        match self.mode {
+            FogMode::Diminish { light } => {
+                let base = (1.0 - light.clamp(0.0, 1.0)) * NUM_COLORMAPS as f32;
+                let index = (base + distance / DIMINISH_STEP) as usize;
+                colormap_level(index.min(NUM_COLORMAPS - 1))
+            }
+            _ => 1.0,
+        }
+    }
+
+    pub fn apply(&self, color: [f32; 4], distance: f32) -> [f32; 4] {
+        let scale = self.colormap_scale(distance);
+        let t = self.factor(distance);
+        let mix = |c: f32, f: f32| (c * scale) * (1.0 - t) + f * t;
+        [
+            mix(color[0], self.color[0]),
+            mix(color[1], self.color[1]),
+            mix(color[2], self.color[2]),
+            color[3],
+        ]
+    }
+}
+
+/// Brightness of colormap `index`; 0 is full bright and the last entry is near black.
+fn colormap_level(index: usize) -> f32 {
+    1.0 - index as f32 / NUM_COLORMAPS as f32
+}
@@ -0,0 +1,215 @@
+use std::fmt::{self, Write};
+
+use crate::difficulty::Difficulty;
+use crate::episode::{Episode, LevelStats, Progress};
+use crate::map::ExitKind;
+use crate::save::SaveGame;
+use crate::world::{Event, TickInput, World};
+
+/// Episode manifest a new game plays through.
+pub const EPISODE: &str = "episode1.txt";
+
+/// A game in progress: the episode, where the player is in it, and the level being played.
+/// Needs no window, renderer or audio device, so it can be run headless.
+pub struct Game {
+    pub episode: Episode,
+    pub progress: Progress,
+    pub world: World,
+    /// Set once the level is over, until `continue_from_intermission` moves on.
+    pub intermission: Option<LevelStats>,
+}
+
+impl Game {
+    /// Starts `episode` from its first map.
+    pub fn new(episode: Episode, difficulty: Difficulty) -> Result<Self, String> {
+        let progress = Progress::start();
+        let world = World::load(&episode.maps[progress.index].name, difficulty)?;
+        Ok(Self { episode, progress, world, intermission: None })
+    }
+
+    /// Starts the episode over with a fresh player.
+    pub fn new_game(&mut self, difficulty: Difficulty) -> Result<(), String> {
+        let progress = Progress::start();
+        self.world = World::load(&self.episode.maps[progress.index].name, difficulty)?;
+        self.progress = progress;
+        self.intermission = None;
+        Ok(())
+    }
+
+    /// Loads the map the episode progress points at and drops the player at its spawn.
+    pub fn load_level(&mut self) -> Result<(), String> {
+        let name = self.episode.maps[self.progress.index].name.clone();
+        self.enter_map(&name)
+    }
+
+    /// Jumps straight to map `name`, keeping the player's stats. Maps outside the episode
+    /// can be loaded too; finishing them carries on from the current episode position.
+    pub fn change_map(&mut self, name: &str) -> Result<(), String> {
+        self.enter_map(name)?;
+        if let Some(index) = self.episode.index_of(name) {
+            self.progress = Progress { index, return_to: None };
+        }
+        Ok(())
+    }
+
+    fn enter_map(&mut self, name: &str) -> Result<(), String> {
+        self.world.enter_map(name)?;
+        self.intermission = None;
+        Ok(())
+    }
+
+    /// Replaces the level with a restored save.
+    pub fn restore(&mut self, save: &SaveGame) -> Result<(), String> {
+        self.world = save.restore()?;
+        self.progress = save.progress;
+        self.intermission = None;
+        Ok(())
+    }
+
+    pub fn floor(&self) -> u32 {
+        self.episode.floor(self.progress.index)
+    }
+
+    /// Runs one tick of the level. An exit ends the level and puts up the intermission, during
+    /// which ticks do nothing.
+    pub fn tick(&mut self, input: &TickInput) -> Vec<Event> {
+        if self.intermission.is_some() {
+            return Vec::new();
+        }
+        let events = self.world.tick(input);
+        for event in &events {
+            if let &Event::Exit(kind) = event {
+                self.finish_level(kind);
+            }
+        }
+        events
+    }
+
+    fn finish_level(&mut self, exit: ExitKind) {
+        let par_secs = self.episode.maps[self.progress.index].par_secs;
+        let floor = self.floor();
+        let more = self.progress.advance(&self.episode, exit == ExitKind::Secret);
+        let world = &self.world;
+        self.intermission = Some(LevelStats {
+            floor,
+            kills: world.kills(),
+            kills_total: world.kills_total(),
+            secrets: world.map.secrets_found(),
+            secrets_total: world.map.secrets_total(),
+            treasure: world.treasure_found(),
+            treasure_total: world.treasure_total(),
+            ticks: world.level_ticks,
+            par_secs,
+            episode_done: !more,
+        });
+    }
+
+    /// Leaves the intermission for the next map. Returns false, leaving the intermission up,
+    /// once the episode is done.
+    pub fn continue_from_intermission(&mut self) -> Result<bool, String> {
+        match self.intermission {
+            Some(stats) if !stats.episode_done => self.load_level().map(|_| true),
+            _ => Ok(false),
+        }
+    }
+
+    /// Hash of everything the simulation depends on. Two games fed the same inputs from the
+    /// same start hash the same after every tick, on any machine.
+    pub fn state_hash(&self) -> u64 {
+        let world = &self.world;
+        let mut h = Fnv::default();
+        // Debug output spells out every field, and floats print exactly.
+        let _ = write!(h, "{} {:?} {:?} {}", world.name, self.progress, world.rng, world.level_ticks);
+        let _ = write!(h, "{:?}", world.player);
+        for (id, e) in world.enemies.iter() {
+            let _ = write!(h, "{id:?}{e:?}");
+        }
+        for (id, item) in world.items.iter() {
+            let _ = write!(h, "{id:?}{item:?}");
+        }
+        for (id, p) in world.projectiles.iter() {
+            let _ = write!(h, "{id:?}{p:?}");
+        }
+        for (id, door) in world.doors.iter() {
+            let _ = write!(h, "{id:?}{door:?}");
+        }
+        let _ = write!(h, "{:?}", world.map.pushwalls);
+        h.0
+    }
+}
+
+/// 64-bit FNV-1a, which unlike `std`'s hasher is fixed forever.
+struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Write for Fnv {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for &b in s.as_bytes() {
+            self.0 = (self.0 ^ b as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+        Ok(())
+    }
+}
+
+/// Parses an input script: one line per run of identical ticks, as read by `parse_run`.
+/// `#` starts a comment line.
+pub fn parse_inputs(text: &str) -> Result<Vec<TickInput>, String> {
+    let mut inputs = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+        let (count, input) = parse_run(line).ok_or_else(|| format!("bad input on line {}: {line}", n + 1))?;
+        inputs.extend(std::iter::repeat_n(input, count));
+    }
+    Ok(inputs)
+}
+
+/// Writes inputs in the form `parse_inputs` reads, folding repeated ticks into one line.
+pub fn format_inputs(inputs: &[TickInput]) -> String {
+    inputs.chunk_by(|a, b| a == b).map(|run| format_run(run.len(), run[0]) + "\n").collect()
+}
+
+/// Reads a run of ticks: the number of ticks, then the actions held for them, any of
+/// `forward`, `back`, `left`, `right`, `fire` and `use`. A run with no actions idles.
+pub fn parse_run(line: &str) -> Option<(usize, TickInput)> {
+    let mut tokens = line.split_whitespace();
+    let count = tokens.next()?.parse().ok()?;
+    let mut input = TickInput::default();
+    for token in tokens {
+        let held = match token {
+            "forward" => &mut input.forward,
+            "back" => &mut input.back,
+            "left" => &mut input.turn_left,
+            "right" => &mut input.turn_right,
+            "fire" => &mut input.fire,
+            "use" => &mut input.use_pressed,
+            _ => return None,
+        };
+        *held = true;
+    }
+    Some((count, input))
+}
+
+pub fn format_run(count: usize, input: TickInput) -> String {
+    let mut out = count.to_string();
+    for (held, name) in [
+        (input.forward, "forward"),
+        (input.back, "back"),
+        (input.turn_left, "left"),
+        (input.turn_right, "right"),
+        (input.fire, "fire"),
+        (input.use_pressed, "use"),
+    ] {
+        if held {
+            out += " ";
+            out += name;
+        }
+    }
+    out
+}
@@ -0,0 +1,59 @@
+use crate::player::{Key, Player, Weapon};
+
+/// Ticks a HUD message stays on screen.
+pub const MESSAGE_TICKS: u32 = 120;
+
+/// Values shown on the Wolf3D-style status bar along the bottom of the screen.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct StatusBar {
+    pub floor: u32,
+    pub score: u32,
+    pub lives: u32,
+    pub health: i32,
+    pub armor: i32,
+    pub ammo: u32,
+    pub weapon: Weapon,
+    pub gold_key: bool,
+    pub silver_key: bool,
+}
+
+impl StatusBar {
+    pub fn new(player: &Player, floor: u32) -> Self {
+        Self {
+            floor,
+            score: player.score,
+            lives: player.lives,
+            health: player.health,
+            armor: player.armor,
+            ammo: player.ammo,
+            weapon: player.weapon,
+            gold_key: player.has_key(Key::Gold),
+            silver_key: player.has_key(Key::Silver),
+        }
+    }
+}
+
+/// Transient text shown across the top of the view.
+#[derive(Default)]
+pub struct Hud {
+    message: Option<(String, u32)>,
+}
+
+impl Hud {
+    pub fn show(&mut self, text: impl Into<String>) {
+        self.message = Some((text.into(), MESSAGE_TICKS));
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_ref().map(|(text, _)| text.as_str())
+    }
+
+    pub fn update(&mut self) {
+        if let Some((_, ticks)) = &mut self.message {
+            *ticks -= 1;
+            if *ticks == 0 {
+                self.message = None;
+            }
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use crate::difficulty::Difficulty;
+use crate::entity::Entities;
+use crate::map::{MAP_HEIGHT, MAP_WIDTH};
+use crate::player::{Player, Weapon, Key, MAX_HEALTH, MAX_LIVES};
+
+pub const MAX_AMMO: u32 = 99;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ItemKind {
+    FirstAid,
+    Food,
+    DogFood,
+    Clip,
+    Cross,
+    Chalice,
+    Chest,
+    Crown,
+    GoldKey,
+    SilverKey,
+    MachineGun,
+    Chaingun,
+    ExtraLife,
+}
+
+impl ItemKind {
+    pub fn from_token(token: &str) -> Option<Self> {
+        Some(match token {
+            "h" => ItemKind::FirstAid,
+            "f" => ItemKind::Food,
+            "d" => ItemKind::DogFood,
+            "a" => ItemKind::Clip,
+            "1" => ItemKind::Cross,
+            "2" => ItemKind::Chalice,
+            "3" => ItemKind::Chest,
+            "4" => ItemKind::Crown,
+            "g" => ItemKind::GoldKey,
+            "s" => ItemKind::SilverKey,
+            "m" => ItemKind::MachineGun,
+            "c" => ItemKind::Chaingun,
+            "l" => ItemKind::ExtraLife,
+            _ => return None,
+        })
+    }
+
+    pub fn token(self) -> &'static str {
+        match self {
+            ItemKind::FirstAid => "h",
+            ItemKind::Food => "f",
+            ItemKind::DogFood => "d",
+            ItemKind::Clip => "a",
+            ItemKind::Cross => "1",
+            ItemKind::Chalice => "2",
+            ItemKind::Chest => "3",
+            ItemKind::Crown => "4",
+            ItemKind::GoldKey => "g",
+            ItemKind::SilverKey => "s",
+            ItemKind::MachineGun => "m",
+            ItemKind::Chaingun => "c",
+            ItemKind::ExtraLife => "l",
+        }
+    }
+
+    /// Treasures count toward the level's treasure ratio.
+    pub fn is_treasure(self) -> bool {
+        matches!(self, ItemKind::Cross | ItemKind::Chalice | ItemKind::Chest | ItemKind::Crown | ItemKind::ExtraLife)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Item {
+    pub kind: ItemKind,
+    pub x: f32,
+    pub y: f32,
+    pub picked: bool,
+    /// Dropped by an enemy during play rather than placed in the map.
+    pub dropped: bool,
+}
+
+/// Parses the item layer: one item token per tile, `0` for none.
+pub fn parse_items(content: &str) -> Vec<Item> {
+    let mut items = Vec::new();
+    let mut row = 0usize;
+    for line in content.lines() {
+        if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+        if row >= MAP_HEIGHT {
+            break;
+        }
+        for (col, token) in line.split_whitespace().enumerate() {
+            if col >= MAP_WIDTH {
+                break;
+            }
+            if let Some(kind) = ItemKind::from_token(token) {
+                items.push(Item {
+                    kind,
+                    x: col as f32 + 0.5,
+                    y: row as f32 + 0.5,
+                    picked: false,
+                    dropped: false,
+                });
+            }
+        }
+        row += 1;
+    }
+    items
+}
+
+/// Gives `kind` to the player following Wolf3D's rules.
+/// Returns false, leaving the item in place, if the player has no use for it.
+/// Ammo amounts are scaled by `difficulty`.
+pub fn pick_up(player: &mut Player, kind: ItemKind, difficulty: Difficulty) -> bool {
+    let ammo = |amount| difficulty.scale_ammo(amount);
+    match kind {
+        ItemKind::FirstAid => heal(player, 25),
+        ItemKind::Food => heal(player, 10),
+        ItemKind::DogFood => heal(player, 4),
+        ItemKind::Clip => {
+            if player.ammo >= MAX_AMMO {
+                return false;
+            }
+            give_ammo(player, ammo(8));
+            true
+        }
+        ItemKind::Cross => give_points(player, 100),
+        ItemKind::Chalice => give_points(player, 500),
+        ItemKind::Chest => give_points(player, 1000),
+        ItemKind::Crown => give_points(player, 5000),
+        ItemKind::GoldKey => {
+            player.give_key(Key::Gold);
+            true
+        }
+        ItemKind::SilverKey => {
+            player.give_key(Key::Silver);
+            true
+        }
+        ItemKind::MachineGun => give_weapon(player, Weapon::MachineGun, ammo(6)),
+        ItemKind::Chaingun => give_weapon(player, Weapon::Chaingun, ammo(6)),
+        ItemKind::ExtraLife => {
+            player.lives = (player.lives + 1).min(MAX_LIVES);
+            player.health = MAX_HEALTH;
+            give_ammo(player, ammo(25));
+            true
+        }
+    }
+}
+
+/// Collects every item on the player's tile. Returns the kinds picked up this tick.
+pub fn touch_items(items: &mut Entities<Item>, player: &mut Player, difficulty: Difficulty) -> Vec<ItemKind> {
+    let (tx, ty) = (player.x as i32, player.y as i32);
+    let mut picked = Vec::new();
+    for item in items.values_mut().filter(|i| !i.picked) {
+        if item.x as i32 == tx && item.y as i32 == ty && pick_up(player, item.kind, difficulty) {
+            item.picked = true;
+            player.bonus_flash = 1.0;
+            picked.push(item.kind);
+        }
+    }
+    picked
+}
+
+fn heal(player: &mut Player, amount: i32) -> bool {
+    if player.health >= MAX_HEALTH {
+        return false;
+    }
+    player.health = (player.health + amount).min(MAX_HEALTH);
+    true
+}
+
+fn give_ammo(player: &mut Player, amount: u32) {
+    player.ammo = (player.ammo + amount).min(MAX_AMMO);
+}
+
+fn give_points(player: &mut Player, points: u32) -> bool {
+    player.score += points;
+    true
+}
+
+fn give_weapon(player: &mut Player, weapon: Weapon, ammo: u32) -> bool {
+    player.weapon = player.weapon.max(weapon);
+    give_ammo(player, ammo);
+    true
+}
@@ -1,10 +1,43 @@
-mod map;
-mod player;
-mod raycaster;
+pub mod actor;
+pub mod ai;
+pub mod area;
+pub mod assets;
+pub mod audio;
+mod automap;
+pub mod bestiary;
+pub mod clock;
+mod commands;
+mod console;
+pub mod cvar;
+pub mod demo;
+pub mod difficulty;
+pub mod door;
+pub mod entity;
+pub mod episode;
+pub mod fog;
+pub mod game;
+pub mod items;
+mod font;
+mod hud;
+pub mod light;
+pub mod map;
+pub mod minimap;
+pub mod path;
+pub mod projectile;
+mod menu;
+pub mod player;
+pub mod pushwall;
+pub mod raycaster;
 mod renderer;
+pub mod rng;
+pub mod save;
+mod sprite;
+pub mod storage;
+pub mod world;
 
 use std::collections::HashSet;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
@@ -16,31 +49,303 @@ use winit::{
     keyboard::{KeyCode, PhysicalKey},
     window::{Window, WindowId},
 };
+use web_time::Instant;
 
-use map::Map;
-use player::Player;
-use renderer::Renderer;
+use audio::{Audio, Music};
+use automap::Automap;
+use clock::TickClock;
+use console::Console;
+use cvar::Cvars;
+use demo::{Demo, Playback};
+use difficulty::Difficulty;
+use episode::Episode;
+use game::Game;
+use hud::{Hud, StatusBar};
+use items::ItemKind;
+use menu::{Menu, MenuAction, Options};
+use minimap::MinimapConfig;
+use renderer::{Renderer, Scene};
+use save::SaveGame;
+use world::{Event, TickInput};
 
 struct App {
     window: Option<Arc<Window>>,
     renderer: Arc<Mutex<Option<Renderer>>>,
-    map: Map,
-    player: Player,
+    game: Game,
+    automap: Automap,
+    hud: Hud,
+    menu: Menu,
+    options: Options,
+    console: Console<App>,
+    cvars: Cvars,
+    audio: Audio,
     keys: HashSet<KeyCode>,
+    /// Use was pressed since the last tick.
+    use_pressed: bool,
+    demo: DemoState,
+    clock: TickClock,
+    /// When the previous frame was drawn.
+    last_frame: Option<Instant>,
+}
+
+/// A demo being recorded or played back. Either way the simulation runs on the demo's settings
+/// rather than the cvars.
+enum DemoState {
+    None,
+    /// Saved to the named slot when recording stops.
+    Recording(Demo, String),
+    Playing(Playback),
 }
 
 impl App {
     fn new() -> Self {
-        let map = Map::load();
-        let (sx, sy, sa) = map.player_start;
-        let player = Player::new(sx, sy, sa);
+        let episode = Episode::load(game::EPISODE).expect("failed to load episode");
+        let game = Game::new(episode, Difficulty::Medium).expect("failed to load map");
         Self {
             window: None,
             renderer: Arc::new(Mutex::new(None)),
-            map,
-            player,
+            game,
+            automap: Automap::new(),
+            hud: Hud::default(),
+            menu: Menu::new(),
+            options: Options::default(),
+            console: Console::new(commands::registry()),
+            cvars: Cvars::load(),
+            audio: Audio::open_default(),
             keys: HashSet::new(),
+            use_pressed: false,
+            demo: DemoState::None,
+            clock: TickClock::default(),
+            last_frame: None,
+        }
+    }
+
+    fn new_game(&mut self, difficulty: Difficulty) {
+        self.stop_demo();
+        match self.game.new_game(difficulty) {
+            Ok(()) => self.reset_level_view(),
+            Err(e) => log::error!("{e}"),
+        }
+    }
+
+    /// Jumps straight to map `name`, keeping the player's stats.
+    fn change_map(&mut self, name: &str) -> Result<(), String> {
+        self.stop_demo();
+        self.game.change_map(name)?;
+        self.reset_level_view();
+        Ok(())
+    }
+
+    /// Clears the automap and HUD for a freshly entered or loaded level.
+    fn reset_level_view(&mut self) {
+        self.automap = Automap::new();
+        self.automap.rotate = self.options.automap_rotate;
+        self.hud = Hud::default();
+    }
+
+    /// Leaves the intermission screen for the next map, or starts over once the episode is done.
+    fn continue_from_intermission(&mut self) {
+        match self.game.continue_from_intermission() {
+            Ok(true) => self.reset_level_view(),
+            Ok(false) => self.menu.open_title(),
+            Err(e) => log::error!("{e}"),
+        }
+    }
+
+    /// Saves the level in play. Between levels the episode progress already points past the
+    /// finished map, so a save then would restore that map with the wrong progress; it is refused.
+    fn save_game(&mut self, slot: &str) {
+        if self.game.intermission.is_some() {
+            log::warn!("can't save between levels");
+            return;
+        }
+        let save = SaveGame::capture(self.game.progress, &self.game.world);
+        match save::write_slot(slot, &save) {
+            Ok(()) => self.hud.show("GAME SAVED"),
+            Err(e) => {
+                log::error!("save failed: {e}");
+                self.hud.show("SAVE FAILED");
+            }
+        }
+    }
+
+    fn load_game(&mut self, slot: &str) {
+        self.stop_demo();
+        match save::read_slot(slot).and_then(|save| self.game.restore(&save)) {
+            Ok(()) => {
+                self.reset_level_view();
+                self.hud.show("GAME LOADED");
+            }
+            Err(e) => {
+                log::error!("load failed: {e}");
+                self.hud.show("LOAD FAILED");
+            }
+        }
+    }
+
+    /// Restarts the current map with a fresh player and records from there into demo slot `name`.
+    fn record_demo(&mut self, name: &str) -> Result<(), String> {
+        // Fail on a bad name now rather than when the recording is saved.
+        storage::slot_path(demo::DEMO_DIR, name, "dmo")?;
+        self.stop_demo();
+        let world = &self.game.world;
+        let demo = Demo::new(
+            &world.name,
+            world.difficulty,
+            world.rng.state,
+            self.cvars.float("move_speed"),
+            self.cvars.float("rot_speed"),
+        );
+        demo.start(&mut self.game)?;
+        self.reset_level_view();
+        self.demo = DemoState::Recording(demo, name.to_string());
+        Ok(())
+    }
+
+    fn play_demo(&mut self, name: &str) -> Result<(), String> {
+        let demo = demo::read_slot(name)?;
+        self.stop_demo();
+        self.demo = DemoState::Playing(Playback::start(demo, &mut self.game)?);
+        self.reset_level_view();
+        Ok(())
+    }
+
+    /// Ends any demo, saving a recording. Playback just stops, leaving the game to the player.
+    fn stop_demo(&mut self) {
+        match std::mem::replace(&mut self.demo, DemoState::None) {
+            DemoState::Recording(demo, name) => match demo::write_slot(&name, &demo) {
+                Ok(()) => self.hud.show(format!("DEMO {} SAVED", name.to_uppercase())),
+                Err(e) => {
+                    log::error!("demo save failed: {e}");
+                    self.hud.show("DEMO SAVE FAILED");
+                }
+            },
+            DemoState::Playing(_) | DemoState::None => {}
+        }
+    }
+
+    fn menu_action(&mut self, action: MenuAction, event_loop: &ActiveEventLoop) {
+        match action {
+            MenuAction::None | MenuAction::Resume | MenuAction::OptionsChanged | MenuAction::EndGame => {}
+            MenuAction::NewGame(difficulty) => self.new_game(difficulty),
+            MenuAction::Load(slot) => self.load_game(&slot),
+            MenuAction::Save(slot) => self.save_game(&slot),
+            MenuAction::Quit => event_loop.exit(),
+        }
+        self.automap.rotate = self.options.automap_rotate;
+    }
+
+    fn run_console_line(&mut self, line: &str) {
+        let mut tokens = line.split_whitespace();
+        let Some(name) = tokens.next() else { return };
+        let args: Vec<&str> = tokens.collect();
+        let result = if let Some(run) = self.console.find(name) {
+            run(self, &args)
+        } else if let Some(var) = self.cvars.get(name).copied() {
+            if args.is_empty() {
+                Ok(format!("{} is {} (default {}) - {}", var.name, var.value, var.default, var.help))
+            } else {
+                self.cvars
+                    .set(name, &args.join(" "))
+                    .and_then(|value| self.cvars.save().map(|_| format!("{name} {value}")))
+            }
+        } else {
+            Err(format!("unknown command {name}"))
+        };
+        match result {
+            Ok(text) => self.console.print(&text),
+            Err(text) => self.console.print(&text),
+        }
+    }
+
+    fn fov(&self) -> f32 {
+        self.cvars.float("fov").to_radians()
+    }
+
+    /// Advances gameplay by one tick and presents what happened.
+    fn tick(&mut self) {
+        let mut input = if self.automap.captures_arrows() {
+            let keys: HashSet<KeyCode> = self.keys.iter()
+                .copied()
+                .filter(|k| !matches!(k, KeyCode::ArrowUp | KeyCode::ArrowDown | KeyCode::ArrowLeft | KeyCode::ArrowRight))
+                .collect();
+            tick_input(&keys)
+        } else {
+            tick_input(&self.keys)
+        };
+        input.use_pressed = std::mem::take(&mut self.use_pressed);
+        let events = match &mut self.demo {
+            DemoState::None => {
+                self.game.world.move_speed = self.cvars.float("move_speed");
+                self.game.world.rot_speed = self.cvars.float("rot_speed");
+                self.game.tick(&input)
+            }
+            DemoState::Recording(demo, _) => {
+                let events = self.game.tick(&input);
+                demo.record(input, &self.game);
+                events
+            }
+            DemoState::Playing(playback) => {
+                let desynced = playback.desync.is_some();
+                let events = playback.tick(&mut self.game).unwrap_or_default();
+                if let (false, Some(tick)) = (desynced, playback.desync) {
+                    log::warn!("demo desynced at tick {tick}");
+                    self.hud.show(format!("DEMO DESYNC AT TICK {tick}"));
+                }
+                if playback.finished() {
+                    self.demo = DemoState::None;
+                    self.hud.show("DEMO ENDED");
+                }
+                events
+            }
+        };
+        // A recording covers one level.
+        if self.game.intermission.is_some() {
+            self.stop_demo();
         }
+        self.audio.set_listener(&self.game.world.player);
+        self.present(events);
+        self.hud.update();
+        self.automap.update(&self.keys);
+        self.audio.advance();
+    }
+
+    /// Plays the sounds and shows the messages of `events`.
+    fn present(&mut self, events: Vec<Event>) {
+        for event in events {
+            match event {
+                Event::Sound(sound) => self.audio.play(sound),
+                Event::SoundAt(sound, x, y) => self.audio.play_at(sound, x, y),
+                Event::Message(text) => self.hud.show(text),
+                // The game has already ended the level and put up the intermission.
+                Event::Exit(_) => {}
+            }
+        }
+    }
+
+    /// Level music while playing, the intermission tune between levels, silence on the title screen.
+    fn update_music(&mut self) {
+        let music = if self.menu.screen() == Some(menu::Screen::Title) {
+            None
+        } else if self.game.intermission.is_some() {
+            Some(Music::Intermission)
+        } else {
+            Some(Music::Level)
+        };
+        self.audio.set_music(music);
+    }
+}
+
+/// Maps the held keys to gameplay actions: `W` `S` `A` `D` or the arrows, and `Ctrl` to fire.
+fn tick_input(keys: &HashSet<KeyCode>) -> TickInput {
+    let held = |a, b| keys.contains(&a) || keys.contains(&b);
+    TickInput {
+        forward: held(KeyCode::KeyW, KeyCode::ArrowUp),
+        back: held(KeyCode::KeyS, KeyCode::ArrowDown),
+        turn_left: held(KeyCode::KeyA, KeyCode::ArrowLeft),
+        turn_right: held(KeyCode::KeyD, KeyCode::ArrowRight),
+        fire: held(KeyCode::ControlLeft, KeyCode::ControlRight),
+        use_pressed: false,
     }
 }
 
@@ -88,16 +393,65 @@ impl ApplicationHandler for App {
                 event: KeyEvent {
                     physical_key: PhysicalKey::Code(key),
                     state,
+                    text,
                     ..
                 },
                 ..
             } => {
+                if state == ElementState::Pressed && key == KeyCode::Backquote && !self.menu.is_open() {
+                    self.console.open = !self.console.open;
+                    self.keys.clear();
+                    return;
+                }
+                if self.console.open {
+                    if state == ElementState::Pressed {
+                        if let Some(line) = self.console.handle_key(key) {
+                            self.run_console_line(&line);
+                        } else if let Some(text) = text {
+                            self.console.type_text(&text);
+                        }
+                    }
+                    return;
+                }
+                if self.menu.is_open() {
+                    if state == ElementState::Pressed {
+                        let action = self.menu.handle_key(key, &mut self.options);
+                        self.menu_action(action, event_loop);
+                    }
+                    return;
+                }
                 match state {
-                    ElementState::Pressed  => { self.keys.insert(key); }
+                    ElementState::Pressed  => {
+                        if self.keys.insert(key) && self.automap.handle_key(key, self.game.world.player.x, self.game.world.player.y) {
+                            self.options.automap_rotate = self.automap.rotate;
+                        }
+                    }
                     ElementState::Released => { self.keys.remove(&key); }
                 }
-                if key == KeyCode::Escape {
-                    event_loop.exit();
+                if key == KeyCode::KeyM && state == ElementState::Pressed {
+                    self.options.minimap_enemies = self.options.minimap_enemies.next();
+                }
+                if matches!(key, KeyCode::Space | KeyCode::KeyE) && state == ElementState::Pressed && self.game.intermission.is_none() {
+                    self.use_pressed = true;
+                }
+                if key == KeyCode::Enter && state == ElementState::Pressed {
+                    if self.game.intermission.is_some() {
+                        self.continue_from_intermission();
+                    } else if self.game.world.player.is_game_over() {
+                        self.new_game(self.game.world.difficulty);
+                    }
+                }
+                if state == ElementState::Pressed && self.game.intermission.is_none() {
+                    match key {
+                        KeyCode::F5 => self.save_game(save::QUICK_SLOT),
+                        KeyCode::F9 => self.load_game(save::QUICK_SLOT),
+                        _ => {}
+                    }
+                }
+                if key == KeyCode::Escape && state == ElementState::Pressed {
+                    // Held movement keys would otherwise stay down behind the menu.
+                    self.keys.clear();
+                    self.menu.open_pause(self.game.intermission.is_none());
                 }
             }
 
@@ -110,11 +464,48 @@ impl ApplicationHandler for App {
             }
 
             WindowEvent::RedrawRequested => {
-                self.player.update(&self.keys, &self.map);
+                let now = Instant::now();
+                let elapsed = self.last_frame.map_or(Duration::ZERO, |last| now - last);
+                self.last_frame = Some(now);
+                for _ in 0..self.clock.advance(elapsed) {
+                    if self.game.intermission.is_some() || self.menu.is_open() || self.console.open {
+                        break;
+                    }
+                    self.tick();
+                }
+                self.update_music();
+
+                let fov = self.fov();
+                let num_rays = self.cvars.int("r_resolution") as usize;
+                let player = &self.game.world.player;
+                let hits = raycaster::cast_rays(player.x, player.y, player.angle, fov, &self.game.world.map, &self.game.world.doors, num_rays);
+                self.automap.reveal(&hits);
+                for item in self.game.world.items.values().filter(|i| matches!(i.kind, ItemKind::GoldKey | ItemKind::SilverKey)) {
+                    if raycaster::point_visible(player.x, player.y, player.angle, fov, &hits, item.x, item.y) {
+                        self.automap.spot(item.x, item.y);
+                    }
+                }
+
+                let status = StatusBar::new(player, self.game.floor());
+                let menu = self.menu.view(&self.options);
+                let scene = Scene {
+                    world: &self.game.world,
+                    hits: &hits,
+                    fov,
+                    ceiling_color: self.cvars.color("r_ceiling_color"),
+                    floor_color: self.cvars.color("r_floor_color"),
+                    automap: &self.automap,
+                    status: &status,
+                    message: self.hud.message(),
+                    intermission: self.game.intermission.as_ref(),
+                    menu: menu.as_ref(),
+                    console: self.console.view(),
+                };
 
                 if let Ok(mut guard) = self.renderer.lock() {
                     if let Some(r) = guard.as_mut() {
-                        match r.render(&self.player, &self.map) {
+                        r.minimap = MinimapConfig::from_cvars(&self.cvars, self.options.minimap_enemies);
+                        match r.render(&scene) {
                             Ok(_) => {}
                             Err(wgpu::SurfaceError::Lost) => {
                                 let size = winit::dpi::PhysicalSize::new(r.config.width, r.config.height);
@@ -0,0 +1,152 @@
+use crate::entity::{Entities, EntityId};
+use crate::map::Map;
+
+/// Ticks per flicker decision, roughly Doom's random light flash cadence.
+const FLICKER_TICKS: u32 = 4;
+const PULSE_SPEED: f32 = 0.05;
+const STROBE_PERIOD: u32 = 35;
+const STROBE_BRIGHT: u32 = 5;
+/// Brightness an animated tile falls to, as a fraction of its own level.
+const EFFECT_MIN: f32 = 0.3;
+const MAX_BRIGHTNESS: f32 = 1.5;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LightEffect {
+    Steady,
+    Flicker,
+    Pulse,
+    Strobe,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TileLight {
+    pub level: f32,
+    pub effect: LightEffect,
+}
+
+impl Default for TileLight {
+    fn default() -> Self {
+        Self { level: 1.0, effect: LightEffect::Steady }
+    }
+}
+
+impl TileLight {
+    pub fn brightness(&self, tick: u32, tx: i32, ty: i32) -> f32 {
+        let low = self.level * EFFECT_MIN;
+        match self.effect {
+            LightEffect::Steady => self.level,
+            LightEffect::Flicker => {
+                if hash(tick / FLICKER_TICKS, tx, ty).is_multiple_of(4) { low } else { self.level }
+            }
+            LightEffect::Pulse => {
+                let t = 0.5 + 0.5 * (tick as f32 * PULSE_SPEED).sin();
+                low + (self.level - low) * t
+            }
+            LightEffect::Strobe => {
+                if tick % STROBE_PERIOD < STROBE_BRIGHT { self.level } else { low }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PointLight {
+    pub x: f32,
+    pub y: f32,
+    pub radius: f32,
+    pub color: [f32; 3],
+    pub effect: LightEffect,
+    /// Remaining ticks for short-lived lights; `None` lives until removed.
+    pub ttl: Option<u32>,
+    pub lifetime: u32,
+}
+
+impl PointLight {
+    pub fn torch(x: f32, y: f32) -> Self {
+        Self { x, y, radius: 3.0, color: [0.9, 0.55, 0.2], effect: LightEffect::Flicker, ttl: None, lifetime: 0 }
+    }
+
+    pub fn muzzle_flash(x: f32, y: f32) -> Self {
+        Self { x, y, radius: 4.0, color: [1.0, 0.9, 0.6], effect: LightEffect::Steady, ttl: Some(4), lifetime: 4 }
+    }
+
+    /// Glow around a rocket or fireball in flight; respawned every tick it flies.
+    pub fn projectile(x: f32, y: f32) -> Self {
+        Self { x, y, radius: 2.0, color: [1.0, 0.6, 0.25], effect: LightEffect::Steady, ttl: Some(1), lifetime: 1 }
+    }
+
+    pub fn explosion(x: f32, y: f32) -> Self {
+        Self { x, y, radius: 5.0, color: [1.0, 0.5, 0.15], effect: LightEffect::Steady, ttl: Some(20), lifetime: 20 }
+    }
+
+    fn contribution(&self, tick: u32, x: f32, y: f32) -> f32 {
+        let d = ((x - self.x).powi(2) + (y - self.y).powi(2)).sqrt();
+        if d >= self.radius {
+            return 0.0;
+        }
+        let effect = TileLight { level: 1.0, effect: self.effect };
+        let fade = match self.ttl {
+            Some(ttl) if self.lifetime > 0 => ttl as f32 / self.lifetime as f32,
+            _ => 1.0,
+        };
+        (1.0 - d / self.radius) * effect.brightness(tick, self.x as i32, self.y as i32) * fade
+    }
+}
+
+/// Animated lighting state: the tick that drives sector effects plus every live point light.
+#[derive(Clone, Debug)]
+pub struct Lighting {
+    pub tick: u32,
+    pub point_lights: Entities<PointLight>,
+}
+
+impl Lighting {
+    pub fn new(map: &Map) -> Self {
+        let point_lights = map.torches.iter().map(|&(x, y)| PointLight::torch(x, y)).collect();
+        Self { tick: 0, point_lights }
+    }
+
+    pub fn spawn(&mut self, light: PointLight) -> EntityId {
+        self.point_lights.spawn(light)
+    }
+
+    pub fn update(&mut self) {
+        self.tick = self.tick.wrapping_add(1);
+        self.point_lights.retain(|_, l| match l.ttl.as_mut() {
+            Some(0) => false,
+            Some(ttl) => {
+                *ttl -= 1;
+                true
+            }
+            None => true,
+        });
+    }
+
+    /// RGB multiplier at world position `(x, y)`: the tile's sector light plus every point light in reach.
+    pub fn light_at(&self, map: &Map, x: f32, y: f32) -> [f32; 3] {
+        let (tx, ty) = (x.floor() as i32, y.floor() as i32);
+        let sector = map.light_at(tx, ty).brightness(self.tick, tx, ty);
+        let mut rgb = [sector; 3];
+        for light in self.point_lights.values() {
+            let c = light.contribution(self.tick, x, y);
+            if c > 0.0 {
+                for (out, col) in rgb.iter_mut().zip(light.color) {
+                    *out += col * c;
+                }
+            }
+        }
+        rgb.map(|v| v.min(MAX_BRIGHTNESS))
+    }
+}
+
+pub fn apply_light(color: [f32; 4], light: [f32; 3]) -> [f32; 4] {
+    [color[0] * light[0], color[1] * light[1], color[2] * light[2], color[3]]
+}
+
+fn hash(a: u32, x: i32, y: i32) -> u32 {
+    let mut h = a.wrapping_mul(0x9E37_79B9) ^ (x as u32).wrapping_mul(0x85EB_CA6B) ^ (y as u32).wrapping_mul(0xC2B2_AE35);
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x7FEB_352D);
+    h ^= h >> 15;
+    h
+}
@@ -1,3 +1,13 @@
+use crate::actor::Dir;
+use crate::area::Areas;
+use crate::assets;
+use crate::door::{Door, DoorKind, DOOR_PASSABLE};
+use crate::entity::{Entities, EntityId};
+use crate::fog::{Fog, FogMode};
+use crate::light::{LightEffect, TileLight};
+use crate::pushwall::{PushWall, PushWallState, PUSHWALL_DISTANCE, PUSHWALL_SPEED};
+use crate::raycaster::{self, RayHit};
+
 pub const MAP_WIDTH: usize = 16;
 pub const MAP_HEIGHT: usize = 16;
 
@@ -5,55 +15,202 @@ pub const MAP_HEIGHT: usize = 16;
 pub enum Cell {
     Empty,
     Wall(u8),
-    Door,
-}
-
-#[derive(Clone, Copy, PartialEq, Debug)]
-pub enum EnemyKind {
-    Guard,
-    Ss,
-    Officer,
+    Door(DoorKind),
+    /// Elevator switch that ends the level when used.
+    Exit(ExitKind),
 }
 
-#[derive(Clone, Debug)]
-#[allow(dead_code)]
-pub struct Enemy {
-    pub kind: EnemyKind,
-    pub x: f32,
-    pub y: f32,
-    pub alive: bool,
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ExitKind {
+    Normal,
+    Secret,
 }
 
 #[allow(dead_code)]
 pub struct Map {
     pub walls: [[Cell; MAP_WIDTH]; MAP_HEIGHT],
-    pub enemies: Vec<Enemy>,
+    /// The door entity in each door tile, once `spawn_doors` has placed them.
+    door_ids: [[Option<EntityId>; MAP_WIDTH]; MAP_HEIGHT],
+    pub pushwalls: Vec<PushWall>,
     pub player_start: (f32, f32, f32),
+    pub fog: Fog,
+    pub light_levels: [[TileLight; MAP_WIDTH]; MAP_HEIGHT],
+    pub torches: Vec<(f32, f32)>,
+    /// Patrol turn markers: a patrolling enemy reaching the tile turns to face this way.
+    pub turns: [[Option<Dir>; MAP_WIDTH]; MAP_HEIGHT],
+    /// Sound areas; rebuilt whenever a push wall changes the floor plan.
+    pub areas: Areas,
 }
 
 impl Map {
-    pub fn load() -> Self {
-        let walls_src   = include_str!("../maps/map1_walls.txt");
-        let enemies_src = include_str!("../maps/map1_enemies.txt");
-        let spawn_src   = include_str!("../maps/map1_spawn.txt");
-        let walls = parse_walls(walls_src);
-        let (enemies, player_start) = parse_actors(enemies_src, spawn_src);
-        Map { walls, enemies, player_start }
+    /// Loads the geometry of map `name` from its layer files `<name>_walls.txt`, `<name>_lights.txt`
+    /// and so on. Only the wall layer is required; every other layer falls back to an empty default.
+    /// Doors, enemies and items are spawned into a `World`; see `spawn_doors`.
+    pub fn load(name: &str) -> Result<Self, String> {
+        let walls_src = assets::load(&format!("{name}_walls.txt"))
+            .ok_or_else(|| format!("map {name} has no {name}_walls.txt"))?;
+        let (walls, pushwalls) = parse_walls(&walls_src);
+        let turns = parse_turns(&load_layer(name, "enemies"));
+        let player_start = parse_spawn(&load_layer(name, "spawn"));
+        let fog = parse_meta(&load_layer(name, "meta"));
+        let (light_levels, torches) = parse_lights(&load_layer(name, "lights"));
+        let areas = Areas::compute(&walls);
+        let door_ids = [[None; MAP_WIDTH]; MAP_HEIGHT];
+        Ok(Map { walls, door_ids, pushwalls, player_start, fog, light_levels, torches, turns, areas })
     }
 
-    pub fn is_solid(&self, x: i32, y: i32) -> bool {
+    /// Spawns a closed door for every door tile, in row-major order, and remembers which tile
+    /// each one fills.
+    pub fn spawn_doors(&mut self) -> Entities<Door> {
+        let mut doors = Entities::default();
+        for (row, cells) in self.walls.iter().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                if let Cell::Door(kind) = *cell {
+                    self.door_ids[row][col] = Some(doors.spawn(Door::new(col as i32, row as i32, kind)));
+                }
+            }
+        }
+        doors
+    }
+
+    pub fn is_solid(&self, doors: &Entities<Door>, x: i32, y: i32) -> bool {
         if x < 0 || y < 0 || x >= MAP_WIDTH as i32 || y >= MAP_HEIGHT as i32 {
             return true;
         }
-        matches!(self.walls[y as usize][x as usize], Cell::Wall(_) | Cell::Door)
+        match self.walls[y as usize][x as usize] {
+            Cell::Empty => self.moving_pushwall_at(x, y).is_some(),
+            Cell::Wall(_) | Cell::Exit(_) => true,
+            Cell::Door(_) => self.door_open(doors, x, y) < DOOR_PASSABLE,
+        }
     }
 
-    #[allow(dead_code)]
     pub fn is_door(&self, x: i32, y: i32) -> bool {
+        matches!(self.cell_at(x, y), Cell::Door(_))
+    }
+
+    /// The door entity filling tile `(x, y)`, if any.
+    pub fn door_id(&self, x: i32, y: i32) -> Option<EntityId> {
         if x < 0 || y < 0 || x >= MAP_WIDTH as i32 || y >= MAP_HEIGHT as i32 {
+            return None;
+        }
+        self.door_ids[y as usize][x as usize]
+    }
+
+    pub fn door_at<'a>(&self, doors: &'a Entities<Door>, x: i32, y: i32) -> Option<&'a Door> {
+        doors.get(self.door_id(x, y)?)
+    }
+
+    pub fn door_at_mut<'a>(&self, doors: &'a mut Entities<Door>, x: i32, y: i32) -> Option<&'a mut Door> {
+        doors.get_mut(self.door_id(x, y)?)
+    }
+
+    /// The sliding push wall overlapping `(x, y)`, if any.
+    pub fn moving_pushwall_at(&self, x: i32, y: i32) -> Option<&PushWall> {
+        self.pushwalls.iter().find(|p| p.is_moving() && p.covers(x, y))
+    }
+
+    /// Starts the idle push wall at `(x, y)` sliding along `dir`.
+    /// Returns false if there is none or the tile behind it is blocked.
+    pub fn push_wall(&mut self, doors: &Entities<Door>, x: i32, y: i32, dir: (i32, i32)) -> bool {
+        let blocked = self.is_solid(doors, x + dir.0, y + dir.1);
+        let Some(pw) = self.pushwalls.iter_mut().find(|p| p.x == x && p.y == y && p.state == PushWallState::Idle) else {
+            return false;
+        };
+        if blocked {
             return false;
         }
-        self.walls[y as usize][x as usize] == Cell::Door
+        pw.dir = dir;
+        pw.state = PushWallState::Moving;
+        self.walls[y as usize][x as usize] = Cell::Empty;
+        self.refresh_areas();
+        true
+    }
+
+    pub fn refresh_areas(&mut self) {
+        self.areas = Areas::compute(&self.walls);
+    }
+
+    /// Slides every moving push wall one tick, settling it as a plain wall at the end of its run
+    /// or as soon as the next tile is blocked.
+    pub fn update_pushwalls(&mut self) {
+        for i in 0..self.pushwalls.len() {
+            if !self.pushwalls[i].is_moving() {
+                continue;
+            }
+            let before = self.pushwalls[i].tile();
+            let pw = &mut self.pushwalls[i];
+            pw.offset = (pw.offset + PUSHWALL_SPEED).min(PUSHWALL_DISTANCE);
+            let (tx, ty) = pw.tile();
+            let (dx, dy) = pw.dir;
+            let finished = pw.offset >= PUSHWALL_DISTANCE;
+
+            // Entering a new tile: stop short if the one after it is not free.
+            let stop = finished || ((tx, ty) != before && self.cell_at(tx + dx, ty + dy) != Cell::Empty);
+            if stop {
+                let pw = &mut self.pushwalls[i];
+                pw.offset = pw.offset.floor();
+                pw.state = PushWallState::Done;
+                let (tx, ty) = pw.tile();
+                self.walls[ty as usize][tx as usize] = Cell::Wall(pw.wall);
+                self.refresh_areas();
+            }
+        }
+    }
+
+    pub fn secrets_total(&self) -> usize {
+        self.pushwalls.len()
+    }
+
+    pub fn secrets_found(&self) -> usize {
+        self.pushwalls.iter().filter(|p| p.state != PushWallState::Idle).count()
+    }
+
+    /// Open fraction of the door at `(x, y)`; 0.0 for anything that is not a door.
+    pub fn door_open(&self, doors: &Entities<Door>, x: i32, y: i32) -> f32 {
+        self.door_at(doors, x, y).map_or(0.0, |d| d.open)
+    }
+
+    /// First wall, door slab or push wall along `dir` from `origin` within `max_dist` tiles.
+    /// Rays pass through the part of a door that has slid open.
+    pub fn raycast(&self, doors: &Entities<Door>, origin: (f32, f32), dir: (f32, f32), max_dist: f32) -> Option<RayHit> {
+        let len = (dir.0 * dir.0 + dir.1 * dir.1).sqrt();
+        if len == 0.0 {
+            return None;
+        }
+        raycaster::trace(origin, (dir.0 / len, dir.1 / len), max_dist, self, doors, |_, _| {})
+    }
+
+    /// True if nothing solid lies on the straight line from `a` to `b`.
+    pub fn line_of_sight(&self, doors: &Entities<Door>, a: (f32, f32), b: (f32, f32)) -> bool {
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        self.raycast(doors, a, (dx, dy), (dx * dx + dy * dy).sqrt()).is_none()
+    }
+
+    /// Tiles a viewer at `origin` can see into within `max_dist`, in row-major order.
+    /// The walls and doors bounding the view are included.
+    pub fn visible_tiles(&self, doors: &Entities<Door>, origin: (f32, f32), max_dist: f32) -> Vec<(i32, i32)> {
+        // Enough rays that neighbours stay less than a tile apart at the edge of the map.
+        const RAYS: usize = 1024;
+        let mut seen = [[false; MAP_WIDTH]; MAP_HEIGHT];
+        for i in 0..RAYS {
+            let angle = i as f32 / RAYS as f32 * std::f32::consts::TAU;
+            raycaster::trace(origin, (angle.cos(), angle.sin()), max_dist, self, doors, |x, y| {
+                if x >= 0 && y >= 0 && x < MAP_WIDTH as i32 && y < MAP_HEIGHT as i32 {
+                    seen[y as usize][x as usize] = true;
+                }
+            });
+        }
+        (0..MAP_HEIGHT as i32)
+            .flat_map(|y| (0..MAP_WIDTH as i32).map(move |x| (x, y)))
+            .filter(|&(x, y)| seen[y as usize][x as usize])
+            .collect()
+    }
+
+    pub fn light_at(&self, x: i32, y: i32) -> TileLight {
+        if x < 0 || y < 0 || x >= MAP_WIDTH as i32 || y >= MAP_HEIGHT as i32 {
+            return TileLight::default();
+        }
+        self.light_levels[y as usize][x as usize]
     }
 
     pub fn cell_at(&self, x: i32, y: i32) -> Cell {
@@ -64,8 +221,9 @@ impl Map {
     }
 }
 
-fn parse_walls(content: &str) -> [[Cell; MAP_WIDTH]; MAP_HEIGHT] {
+fn parse_walls(content: &str) -> ([[Cell; MAP_WIDTH]; MAP_HEIGHT], Vec<PushWall>) {
     let mut grid = [[Cell::Empty; MAP_WIDTH]; MAP_HEIGHT];
+    let mut pushwalls = Vec::new();
     let mut row = 0;
     for line in content.lines() {
         if line.starts_with('#') || line.trim().is_empty() {
@@ -80,19 +238,40 @@ fn parse_walls(content: &str) -> [[Cell; MAP_WIDTH]; MAP_HEIGHT] {
             }
             grid[row][col] = match token {
                 "0" => Cell::Empty,
-                "4" => Cell::Door,
-                v => Cell::Wall(v.parse::<u8>().unwrap_or(1)),
+                "4" => Cell::Door(DoorKind::Normal),
+                "5" => Cell::Door(DoorKind::GoldLocked),
+                "6" => Cell::Door(DoorKind::SilverLocked),
+                "7" => Cell::Door(DoorKind::Elevator),
+                "8" => Cell::Exit(ExitKind::Normal),
+                "9" => Cell::Exit(ExitKind::Secret),
+                v => match v.strip_suffix('p') {
+                    Some(n) => {
+                        let wall = n.parse::<u8>().unwrap_or(1);
+                        pushwalls.push(PushWall::new(col as i32, row as i32, wall));
+                        Cell::Wall(wall)
+                    }
+                    None => Cell::Wall(v.parse::<u8>().unwrap_or(1)),
+                },
             };
         }
         row += 1;
     }
-    grid
+    (grid, pushwalls)
+}
+
+/// Text of layer `<name>_<suffix>.txt`, empty if the map has none.
+pub fn load_layer(name: &str, suffix: &str) -> String {
+    assets::load(&format!("{name}_{suffix}.txt")).unwrap_or_default()
 }
 
-fn parse_actors(enemy_content: &str, spawn_content: &str) -> (Vec<Enemy>, (f32, f32, f32)) {
-    let mut enemies = Vec::new();
+/// Turn markers per tile, as parsed from the enemy layer.
+type Turns = [[Option<Dir>; MAP_WIDTH]; MAP_HEIGHT];
+
+/// Patrol turn markers: the lone facings (`^ > v <`) in the enemy layer.
+fn parse_turns(content: &str) -> Turns {
+    let mut turns = [[None; MAP_WIDTH]; MAP_HEIGHT];
     let mut row = 0usize;
-    for line in enemy_content.lines() {
+    for line in content.lines() {
         if line.starts_with('#') || line.trim().is_empty() {
             continue;
         }
@@ -100,30 +279,19 @@ fn parse_actors(enemy_content: &str, spawn_content: &str) -> (Vec<Enemy>, (f32,
             break;
         }
         for (col, token) in line.split_whitespace().enumerate() {
-            if col >= MAP_WIDTH {
-                break;
-            }
-            let kind = match token {
-                "1" => Some(EnemyKind::Guard),
-                "2" => Some(EnemyKind::Ss),
-                "3" => Some(EnemyKind::Officer),
-                _ => None,
-            };
-            if let Some(k) = kind {
-                enemies.push(Enemy {
-                    kind: k,
-                    x: col as f32 + 0.5,
-                    y: row as f32 + 0.5,
-                    alive: true,
-                });
+            let mut chars = token.chars();
+            if let (Some(dir), None, true) = (chars.next().and_then(Dir::from_char), chars.next(), col < MAP_WIDTH) {
+                turns[row][col] = Some(dir);
             }
         }
         row += 1;
     }
+    turns
+}
 
-    let mut player_start = (1.5f32, 1.5f32, 0.0f32);
+fn parse_spawn(content: &str) -> (f32, f32, f32) {
     let mut srow = 0usize;
-    'outer: for line in spawn_content.lines() {
+    for line in content.lines() {
         if line.starts_with('#') || line.trim().is_empty() {
             continue;
         }
@@ -132,12 +300,81 @@ fn parse_actors(enemy_content: &str, spawn_content: &str) -> (Vec<Enemy>, (f32,
         }
         for (col, token) in line.split_whitespace().enumerate() {
             if token == "P" {
-                player_start = (col as f32 + 0.5, srow as f32 + 0.5, 0.0);
-                break 'outer;
+                return (col as f32 + 0.5, srow as f32 + 0.5, 0.0);
             }
         }
         srow += 1;
     }
+    (1.5, 1.5, 0.0)
+}
+
+fn parse_lights(content: &str) -> ([[TileLight; MAP_WIDTH]; MAP_HEIGHT], Vec<(f32, f32)>) {
+    let mut grid = [[TileLight::default(); MAP_WIDTH]; MAP_HEIGHT];
+    let mut torches = Vec::new();
+    let mut row = 0;
+    for line in content.lines() {
+        if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+        if row >= MAP_HEIGHT {
+            break;
+        }
+        for (col, token) in line.split_whitespace().enumerate() {
+            if col >= MAP_WIDTH {
+                break;
+            }
+            let mut chars = token.chars();
+            let level = chars.next().and_then(|c| c.to_digit(10)).unwrap_or(9);
+            let mut effect = LightEffect::Steady;
+            for c in chars {
+                match c {
+                    'f' => effect = LightEffect::Flicker,
+                    'p' => effect = LightEffect::Pulse,
+                    's' => effect = LightEffect::Strobe,
+                    '*' => torches.push((col as f32 + 0.5, row as f32 + 0.5)),
+                    _ => {}
+                }
+            }
+            grid[row][col] = TileLight { level: level as f32 / 9.0, effect };
+        }
+        row += 1;
+    }
+    (grid, torches)
+}
+
+fn parse_meta(content: &str) -> Fog {
+    let mut fog = Fog::default();
+    let mut mode = "none";
+    let (mut start, mut end, mut density, mut light) = (4.0f32, 16.0f32, 0.15f32, 1.0f32);
+
+    for line in content.lines() {
+        if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "fog" => mode = value,
+            "fog_color" => {
+                for (i, c) in value.split_whitespace().take(3).enumerate() {
+                    fog.color[i] = c.parse().unwrap_or(0.0);
+                }
+            }
+            "fog_start" => start = value.parse().unwrap_or(start),
+            "fog_end" => end = value.parse().unwrap_or(end),
+            "fog_density" => density = value.parse().unwrap_or(density),
+            "light" => light = value.parse().unwrap_or(light),
+            _ => {}
+        }
+    }
 
-    (enemies, player_start)
+    fog.mode = match mode {
+        "linear" => FogMode::Linear { start, end },
+        "exp" => FogMode::Exponential { density },
+        "diminish" => FogMode::Diminish { light },
+        _ => FogMode::None,
+    };
+    fog
 }
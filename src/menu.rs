@@ -0,0 +1,257 @@
+use winit::keyboard::KeyCode;
+
+use crate::difficulty::Difficulty;
+use crate::minimap::MinimapEnemies;
+use crate::save;
+
+/// Named save slots offered by the save and load screens, besides the quicksave.
+pub const SAVE_SLOTS: [&str; 4] = ["slot1", "slot2", "slot3", "slot4"];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Screen {
+    Title,
+    Difficulty,
+    Load,
+    Save,
+    Options,
+    /// In-game menu drawn over the frozen view.
+    Pause,
+    Confirm(Confirm),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Confirm {
+    Quit,
+    EndGame,
+}
+
+/// Player preferences edited on the options screen.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Options {
+    pub minimap_enemies: MinimapEnemies,
+    pub automap_rotate: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self { minimap_enemies: MinimapEnemies::Visible, automap_rotate: false }
+    }
+}
+
+/// What the app should do in response to a menu selection.
+#[derive(Clone, PartialEq, Debug)]
+pub enum MenuAction {
+    None,
+    Resume,
+    NewGame(Difficulty),
+    Load(String),
+    Save(String),
+    /// An option changed; the app re-applies `Options`.
+    OptionsChanged,
+    EndGame,
+    Quit,
+}
+
+/// Everything the renderer needs to draw the open menu.
+pub struct MenuView {
+    pub title: &'static str,
+    pub items: Vec<String>,
+    pub cursor: usize,
+    /// Drawn over the game view rather than on its own backdrop.
+    pub overlay: bool,
+}
+
+/// Menu state machine. Screens stack, so Escape always returns to the one that led here.
+pub struct Menu {
+    stack: Vec<Screen>,
+    cursor: usize,
+    /// Which save slots hold a save, refreshed when a save or load screen opens.
+    slots_used: Vec<bool>,
+    /// Whether the pause menu offers saving; off between levels, when there is no level to save.
+    can_save: bool,
+}
+
+impl Menu {
+    /// Starts on the title screen.
+    pub fn new() -> Self {
+        Self { stack: vec![Screen::Title], cursor: 0, slots_used: Vec::new(), can_save: true }
+    }
+
+    pub fn is_open(&self) -> bool {
+        !self.stack.is_empty()
+    }
+
+    pub fn screen(&self) -> Option<Screen> {
+        self.stack.last().copied()
+    }
+
+    pub fn open_pause(&mut self, can_save: bool) {
+        self.stack = vec![Screen::Pause];
+        self.can_save = can_save;
+        self.cursor = 0;
+    }
+
+    pub fn open_title(&mut self) {
+        self.stack = vec![Screen::Title];
+        self.cursor = 0;
+    }
+
+    pub fn close(&mut self) {
+        self.stack.clear();
+    }
+
+    fn push(&mut self, screen: Screen) {
+        if matches!(screen, Screen::Load | Screen::Save) {
+            self.slots_used = std::iter::once(save::QUICK_SLOT)
+                .chain(SAVE_SLOTS)
+                .map(save::slot_exists)
+                .collect();
+        }
+        self.stack.push(screen);
+        self.cursor = 0;
+    }
+
+    fn back(&mut self) -> MenuAction {
+        match self.screen() {
+            Some(Screen::Pause) => {
+                self.close();
+                MenuAction::Resume
+            }
+            Some(Screen::Title) | None => MenuAction::None,
+            Some(_) => {
+                self.stack.pop();
+                self.cursor = 0;
+                MenuAction::None
+            }
+        }
+    }
+
+    fn items(&self, options: &Options) -> Vec<String> {
+        let slot_label = |i: usize, name: &str| {
+            let used = self.slots_used.get(i).copied().unwrap_or(false);
+            format!("{name} - {}", if used { "SAVED" } else { "EMPTY" })
+        };
+        match self.screen() {
+            Some(Screen::Title) => {
+                let mut items = vec!["NEW GAME".to_string(), "LOAD GAME".to_string(), "OPTIONS".to_string()];
+                // A browser tab cannot close itself.
+                if cfg!(not(target_arch = "wasm32")) {
+                    items.push("QUIT".to_string());
+                }
+                items
+            }
+            Some(Screen::Difficulty) => Difficulty::ALL.iter().map(|d| d.name().to_string()).collect(),
+            Some(Screen::Load) => std::iter::once("QUICKSAVE")
+                .chain(["SLOT 1", "SLOT 2", "SLOT 3", "SLOT 4"])
+                .enumerate()
+                .map(|(i, name)| slot_label(i, name))
+                .collect(),
+            Some(Screen::Save) => ["SLOT 1", "SLOT 2", "SLOT 3", "SLOT 4"]
+                .iter()
+                .enumerate()
+                .map(|(i, name)| slot_label(i + 1, name))
+                .collect(),
+            Some(Screen::Options) => vec![
+                format!("MINIMAP ENEMIES: {}", options.minimap_enemies.label()),
+                format!("AUTOMAP ROTATE: {}", if options.automap_rotate { "ON" } else { "OFF" }),
+                "BACK".to_string(),
+            ],
+            Some(Screen::Pause) => {
+                let save = if self.can_save { "SAVE GAME" } else { "CAN'T SAVE NOW" };
+                ["RESUME", save, "LOAD GAME", "OPTIONS", "END GAME"].iter().map(|s| s.to_string()).collect()
+            }
+            Some(Screen::Confirm(_)) => vec!["NO".to_string(), "YES".to_string()],
+            None => Vec::new(),
+        }
+    }
+
+    pub fn view(&self, options: &Options) -> Option<MenuView> {
+        let screen = self.screen()?;
+        let title = match screen {
+            Screen::Title => "WOLF3D-RS",
+            Screen::Difficulty => "HOW TOUGH ARE YOU?",
+            Screen::Load => "LOAD GAME",
+            Screen::Save => "SAVE GAME",
+            Screen::Options => "OPTIONS",
+            Screen::Pause => "PAUSED",
+            Screen::Confirm(Confirm::Quit) => "REALLY QUIT?",
+            Screen::Confirm(Confirm::EndGame) => "END THIS GAME?",
+        };
+        // Everything opened from the pause menu stays an overlay on the game.
+        let overlay = self.stack.first() == Some(&Screen::Pause);
+        Some(MenuView { title, items: self.items(options), cursor: self.cursor, overlay })
+    }
+
+    /// Handles a key press while the menu is open.
+    pub fn handle_key(&mut self, key: KeyCode, options: &mut Options) -> MenuAction {
+        let count = self.items(options).len();
+        match key {
+            KeyCode::ArrowUp | KeyCode::KeyW => {
+                self.cursor = (self.cursor + count - 1) % count;
+                MenuAction::None
+            }
+            KeyCode::ArrowDown | KeyCode::KeyS => {
+                self.cursor = (self.cursor + 1) % count;
+                MenuAction::None
+            }
+            KeyCode::Escape => self.back(),
+            KeyCode::KeyY if matches!(self.screen(), Some(Screen::Confirm(_))) => {
+                self.cursor = 1;
+                self.select(options)
+            }
+            KeyCode::KeyN if matches!(self.screen(), Some(Screen::Confirm(_))) => self.back(),
+            KeyCode::Enter | KeyCode::Space => self.select(options),
+            _ => MenuAction::None,
+        }
+    }
+
+    fn select(&mut self, options: &mut Options) -> MenuAction {
+        let Some(screen) = self.screen() else { return MenuAction::None };
+        match (screen, self.cursor) {
+            (Screen::Title, 0) => self.push(Screen::Difficulty),
+            (Screen::Title, 1) | (Screen::Pause, 2) => self.push(Screen::Load),
+            (Screen::Title, 2) | (Screen::Pause, 3) => self.push(Screen::Options),
+            (Screen::Title, 3) => self.push(Screen::Confirm(Confirm::Quit)),
+            (Screen::Difficulty, i) => {
+                self.close();
+                return MenuAction::NewGame(Difficulty::ALL[i]);
+            }
+            (Screen::Load, i) => {
+                let slot = if i == 0 { save::QUICK_SLOT } else { SAVE_SLOTS[i - 1] };
+                if !self.slots_used[i] {
+                    return MenuAction::None;
+                }
+                self.close();
+                return MenuAction::Load(slot.to_string());
+            }
+            (Screen::Save, i) => {
+                self.close();
+                return MenuAction::Save(SAVE_SLOTS[i].to_string());
+            }
+            (Screen::Options, 0) => {
+                options.minimap_enemies = options.minimap_enemies.next();
+                return MenuAction::OptionsChanged;
+            }
+            (Screen::Options, 1) => {
+                options.automap_rotate = !options.automap_rotate;
+                return MenuAction::OptionsChanged;
+            }
+            (Screen::Options, _) => return self.back(),
+            (Screen::Pause, 0) => {
+                self.close();
+                return MenuAction::Resume;
+            }
+            (Screen::Pause, 1) if self.can_save => self.push(Screen::Save),
+            (Screen::Pause, 1) => {}
+            (Screen::Pause, _) => self.push(Screen::Confirm(Confirm::EndGame)),
+            (Screen::Confirm(_), 0) => return self.back(),
+            (Screen::Confirm(Confirm::Quit), _) => return MenuAction::Quit,
+            (Screen::Confirm(Confirm::EndGame), _) => {
+                self.open_title();
+                return MenuAction::EndGame;
+            }
+            _ => {}
+        }
+        MenuAction::None
+    }
+}
@@ -0,0 +1,83 @@
+use crate::cvar::Cvars;
+use crate::map::{MAP_HEIGHT, MAP_WIDTH};
+use crate::raycaster::VIEW_H;
+
+/// Default size of a minimap tile in pixels.
+pub const MINIMAP_TILE_PX: f32 = 8.0;
+/// Default width and height of the minimap window in tiles.
+pub const MINIMAP_TILES: usize = 12;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MinimapEnemies {
+    Hidden,
+    /// Only enemies inside the view cone and in front of the wall hit by their column.
+    Visible,
+    All,
+}
+
+impl MinimapEnemies {
+    pub fn next(self) -> Self {
+        match self {
+            MinimapEnemies::Hidden => MinimapEnemies::Visible,
+            MinimapEnemies::Visible => MinimapEnemies::All,
+            MinimapEnemies::All => MinimapEnemies::Hidden,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MinimapEnemies::Hidden => "HIDDEN",
+            MinimapEnemies::Visible => "VISIBLE",
+            MinimapEnemies::All => "ALL",
+        }
+    }
+}
+
+/// Placement and look of the corner minimap, in screen pixels.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct MinimapConfig {
+    pub x: f32,
+    pub y: f32,
+    pub tile_px: f32,
+    /// Width and height of the minimap window in tiles; larger maps scroll around the player.
+    pub view_tiles: usize,
+    pub opacity: f32,
+    pub enemies: MinimapEnemies,
+}
+
+impl Default for MinimapConfig {
+    fn default() -> Self {
+        Self {
+            x: 8.0,
+            y: VIEW_H as f32 - 8.0 - MINIMAP_TILE_PX * MINIMAP_TILES as f32,
+            tile_px: MINIMAP_TILE_PX,
+            view_tiles: MINIMAP_TILES,
+            opacity: 0.8,
+            enemies: MinimapEnemies::Visible,
+        }
+    }
+}
+
+impl MinimapConfig {
+    /// The `r_minimap_*` cvars, showing `enemies`.
+    pub fn from_cvars(cvars: &Cvars, enemies: MinimapEnemies) -> Self {
+        Self {
+            x: cvars.float("r_minimap_x"),
+            y: cvars.float("r_minimap_y"),
+            tile_px: cvars.float("r_minimap_size"),
+            view_tiles: cvars.int("r_minimap_tiles") as usize,
+            opacity: cvars.float("r_minimap_opacity"),
+            enemies,
+        }
+    }
+
+    /// Map position of the window's top-left corner for a player at `(x, y)`: centred on the
+    /// player, but never past the map edge, and fixed along axes the whole map fits in.
+    pub fn scroll(&self, x: f32, y: f32) -> (f32, f32) {
+        let view = self.view_tiles as f32;
+        let scroll = |pos: f32, extent: usize| {
+            if extent as f32 <= view { 0.0 } else { (pos - view / 2.0).clamp(0.0, extent as f32 - view) }
+        };
+        (scroll(x, MAP_WIDTH), scroll(y, MAP_HEIGHT))
+    }
+}
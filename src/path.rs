@@ -0,0 +1,192 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::door::{Door, DOOR_PASSABLE};
+use crate::entity::Entities;
+use crate::map::{Cell, Map, MAP_HEIGHT, MAP_WIDTH};
+
+/// Cost of one straight step. Diagonals cost `DIAGONAL_COST`, roughly `STEP_COST * sqrt(2)`.
+pub const STEP_COST: u32 = 10;
+pub const DIAGONAL_COST: u32 = 14;
+/// Extra cost of stepping into a door that is not open yet, standing in for the wait.
+pub const DOOR_COST: u32 = 40;
+
+const DIRS: [(i32, i32); 8] = [(1, 0), (-1, 0), (0, 1), (0, -1), (1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+/// How an actor can enter a tile.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Tile {
+    Blocked,
+    Floor,
+    /// A door, passable once opened; `true` if it is open far enough to walk through now.
+    Door(bool),
+}
+
+fn tile(map: &Map, doors: &Entities<Door>, x: i32, y: i32) -> Tile {
+    match map.cell_at(x, y) {
+        Cell::Empty if map.moving_pushwall_at(x, y).is_none() => Tile::Floor,
+        Cell::Door(_) => Tile::Door(map.door_open(doors, x, y) >= DOOR_PASSABLE),
+        _ => Tile::Blocked,
+    }
+}
+
+/// Cost of stepping from `(x, y)` by `(dx, dy)`, or `None` if the move is not allowed.
+/// Diagonal steps need open floor on both sides so actors never clip a wall corner,
+/// and doors are only entered and left straight on.
+fn step_cost(map: &Map, doors: &Entities<Door>, x: i32, y: i32, (dx, dy): (i32, i32)) -> Option<u32> {
+    let to = tile(map, doors, x + dx, y + dy);
+    if dx != 0 && dy != 0 {
+        let clear = [tile(map, doors, x, y), to, tile(map, doors, x + dx, y), tile(map, doors, x, y + dy)];
+        return clear.iter().all(|t| *t == Tile::Floor).then_some(DIAGONAL_COST);
+    }
+    match to {
+        Tile::Blocked => None,
+        Tile::Floor | Tile::Door(true) => Some(STEP_COST),
+        Tile::Door(false) => Some(STEP_COST + DOOR_COST),
+    }
+}
+
+fn in_bounds(x: i32, y: i32) -> bool {
+    x >= 0 && y >= 0 && x < MAP_WIDTH as i32 && y < MAP_HEIGHT as i32
+}
+
+/// Octile distance: exact on an open grid, so A* stays optimal.
+fn heuristic((ax, ay): (i32, i32), (bx, by): (i32, i32)) -> u32 {
+    let (dx, dy) = (ax.abs_diff(bx), ay.abs_diff(by));
+    STEP_COST * dx.max(dy) + (DIAGONAL_COST - STEP_COST) * dx.min(dy)
+}
+
+/// Cheapest route from tile `from` to tile `to` by A*. The path lists every tile stepped onto,
+/// ending with `to`; it is empty if the two are the same tile. `None` if `to` cannot be reached.
+pub fn find_path(map: &Map, doors: &Entities<Door>, from: (i32, i32), to: (i32, i32)) -> Option<Vec<(i32, i32)>> {
+    if !in_bounds(from.0, from.1) || !in_bounds(to.0, to.1) || tile(map, doors, to.0, to.1) == Tile::Blocked {
+        return None;
+    }
+    let index = |(x, y): (i32, i32)| y as usize * MAP_WIDTH + x as usize;
+    let mut cost = vec![u32::MAX; MAP_WIDTH * MAP_HEIGHT];
+    let mut came_from = vec![None; MAP_WIDTH * MAP_HEIGHT];
+    let mut open = BinaryHeap::new();
+    cost[index(from)] = 0;
+    open.push(Reverse((heuristic(from, to), from)));
+
+    while let Some(Reverse((_, pos))) = open.pop() {
+        if pos == to {
+            let mut path = Vec::new();
+            let mut at = to;
+            while at != from {
+                path.push(at);
+                at = came_from[index(at)].expect("every reached tile but the start has a parent");
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for dir in DIRS {
+            let next = (pos.0 + dir.0, pos.1 + dir.1);
+            let Some(step) = step_cost(map, doors, pos.0, pos.1, dir) else { continue };
+            let c = cost[index(pos)] + step;
+            if c < cost[index(next)] {
+                cost[index(next)] = c;
+                came_from[index(next)] = Some(pos);
+                open.push(Reverse((c + heuristic(next, to), next)));
+            }
+        }
+    }
+    None
+}
+
+/// Distance to a target from every tile, for steering any number of actors toward it.
+#[derive(Clone, Debug)]
+pub struct FlowField {
+    pub target: (i32, i32),
+    cost: [[u32; MAP_WIDTH]; MAP_HEIGHT],
+}
+
+impl FlowField {
+    /// Dijkstra outward from `target`, pricing each move in the direction an actor would walk it,
+    /// so going downhill from any tile follows a cheapest path to the target.
+    pub fn build(map: &Map, doors: &Entities<Door>, target: (i32, i32)) -> Self {
+        let mut cost = [[u32::MAX; MAP_WIDTH]; MAP_HEIGHT];
+        let mut open = BinaryHeap::new();
+        if in_bounds(target.0, target.1) {
+            cost[target.1 as usize][target.0 as usize] = 0;
+            open.push(Reverse((0, target)));
+        }
+        while let Some(Reverse((c, (x, y)))) = open.pop() {
+            if c > cost[y as usize][x as usize] {
+                continue;
+            }
+            for (dx, dy) in DIRS {
+                // Step costs depend on the tile entered, so price the move in the walking direction.
+                let (nx, ny) = (x + dx, y + dy);
+                if !in_bounds(nx, ny) {
+                    continue;
+                }
+                let Some(step) = step_cost(map, doors, nx, ny, (-dx, -dy)) else { continue };
+                let next = c + step;
+                if next < cost[ny as usize][nx as usize] {
+                    cost[ny as usize][nx as usize] = next;
+                    open.push(Reverse((next, (nx, ny))));
+                }
+            }
+        }
+        Self { target, cost }
+    }
+
+    /// Path cost from `(x, y)` to the target; `None` if it cannot be reached.
+    pub fn cost(&self, x: i32, y: i32) -> Option<u32> {
+        if !in_bounds(x, y) {
+            return None;
+        }
+        Some(self.cost[y as usize][x as usize]).filter(|&c| c != u32::MAX)
+    }
+
+    /// Step to take from `(x, y)` toward the target; `None` at the target or when it is unreachable.
+    pub fn direction(&self, map: &Map, doors: &Entities<Door>, x: i32, y: i32) -> Option<(i32, i32)> {
+        let here = self.cost(x, y)?;
+        DIRS.into_iter()
+            .filter_map(|dir| {
+                let step = step_cost(map, doors, x, y, dir)?;
+                let there = self.cost(x + dir.0, y + dir.1)?;
+                (there + step == here).then_some((dir, there))
+            })
+            .min_by_key(|&(_, there)| there)
+            .map(|(dir, _)| dir)
+    }
+}
+
+/// Door and push wall state that routes depend on.
+fn nav_state(map: &Map, doors: &Entities<Door>) -> Vec<(i32, i32, bool)> {
+    let doors = doors.values().map(|d| (d.x, d.y, d.open >= DOOR_PASSABLE));
+    let pushwalls = map.pushwalls.iter().map(|p| {
+        let (x, y) = p.tile();
+        (x, y, p.is_moving())
+    });
+    doors.chain(pushwalls).collect()
+}
+
+/// A flow field toward a moving target, rebuilt only when the target changes tile or a door or
+/// push wall changes what can be walked through.
+#[derive(Default)]
+pub struct FlowCache {
+    field: Option<FlowField>,
+    state: Vec<(i32, i32, bool)>,
+    builds: u32,
+}
+
+impl FlowCache {
+    pub fn toward(&mut self, map: &Map, doors: &Entities<Door>, target: (i32, i32)) -> &FlowField {
+        let state = nav_state(map, doors);
+        let stale = self.field.as_ref().is_none_or(|f| f.target != target) || state != self.state;
+        if stale {
+            self.field = Some(FlowField::build(map, doors, target));
+            self.state = state;
+            self.builds += 1;
+        }
+        self.field.as_ref().expect("flow field was just built")
+    }
+
+    /// How many times the field has been built.
+    pub fn builds(&self) -> u32 {
+        self.builds
+    }
+}
@@ -1,46 +1,357 @@
-use std::collections::HashSet;
-use winit::keyboard::KeyCode;
+use crate::actor::Enemy;
+use crate::door::Door;
+use crate::entity::{Entities, EntityId};
+use crate::world::TickInput;
 
+/// Default walking speed; the `move_speed` cvar overrides it.
 pub const MOVE_SPEED: f32 = 0.05;
+/// Default turning speed; the `rot_speed` cvar overrides it.
 pub const ROT_SPEED: f32 = 0.04;
 
+pub const MAX_HEALTH: i32 = 100;
+pub const MAX_ARMOR: i32 = 100;
+pub const START_LIVES: u32 = 3;
+/// Extra lives beyond this still heal and give ammo, as in Wolf3D.
+pub const MAX_LIVES: u32 = 9;
+pub const START_AMMO: u32 = 8;
+/// Ticks after taking damage during which further hits are ignored.
+pub const PAIN_COOLDOWN: u32 = 20;
+/// Ticks the camera takes to drop to the floor once dead.
+pub const DEATH_DROP_TICKS: u32 = 40;
+/// Ticks from death until respawn or game over; the screen fades out over this span.
+pub const DEATH_TICKS: u32 = 120;
+/// Eye height as a fraction of wall height.
+pub const EYE_HEIGHT: f32 = 0.5;
+const DEAD_EYE_HEIGHT: f32 = 0.1;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Key {
+    Gold,
+    Silver,
+}
+
+impl Key {
+    pub fn name(self) -> &'static str {
+        match self {
+            Key::Gold => "GOLD",
+            Key::Silver => "SILVER",
+        }
+    }
+
+    fn bit(self) -> u8 {
+        match self {
+            Key::Gold => 1,
+            Key::Silver => 2,
+        }
+    }
+}
+
+/// What happened when the player pressed use.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum UseOutcome {
+    Nothing,
+    Door,
+    Locked(Key),
+    Secret,
+    /// Flipped an exit switch; the level is over.
+    Exit(crate::map::ExitKind),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LifeState {
+    Alive,
+    Dying { ticks: u32 },
+    GameOver,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Weapon {
+    Knife,
+    Pistol,
+    MachineGun,
+    Chaingun,
+}
+
+impl Weapon {
+    pub fn name(self) -> &'static str {
+        match self {
+            Weapon::Knife => "KNIFE",
+            Weapon::Pistol => "PISTOL",
+            Weapon::MachineGun => "MACHINE GUN",
+            Weapon::Chaingun => "CHAINGUN",
+        }
+    }
+
+    /// Ticks between shots; holding fire repeats at this rate.
+    pub fn cooldown(self) -> u32 {
+        match self {
+            Weapon::Knife | Weapon::Pistol => 20,
+            Weapon::MachineGun => 10,
+            Weapon::Chaingun => 5,
+        }
+    }
+
+    /// Health taken off an enemy per hit.
+    pub fn damage(self) -> i32 {
+        match self {
+            Weapon::Knife => 15,
+            Weapon::Pistol | Weapon::MachineGun => 20,
+            Weapon::Chaingun => 25,
+        }
+    }
+
+    /// Reach in tiles; guns are stopped only by walls.
+    pub fn range(self) -> f32 {
+        match self {
+            Weapon::Knife => 1.2,
+            _ => f32::INFINITY,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct Player {
     pub x: f32,
     pub y: f32,
     pub angle: f32,
+    pub health: i32,
+    pub armor: i32,
+    pub lives: u32,
+    pub score: u32,
+    pub ammo: u32,
+    pub weapon: Weapon,
+    pub state: LifeState,
+    pub pain_cooldown: u32,
+    /// Red screen flash strength, 0.0 - 1.0, decaying every tick.
+    pub damage_flash: f32,
+    /// Gold pickup flash strength, 0.0 - 1.0, decaying every tick.
+    pub bonus_flash: f32,
+    /// Bitset of held `Key`s; lost on death.
+    pub keys: u8,
+    /// Ticks until the weapon can fire again.
+    pub fire_cooldown: u32,
+    /// Console cheat: walk through walls.
+    pub noclip: bool,
+    /// Console cheat: ignore damage.
+    pub god: bool,
 }
 
 impl Player {
     pub fn new(x: f32, y: f32, angle: f32) -> Self {
-        Self { x, y, angle }
+        Self {
+            x,
+            y,
+            angle,
+            health: MAX_HEALTH,
+            armor: 0,
+            lives: START_LIVES,
+            score: 0,
+            ammo: START_AMMO,
+            weapon: Weapon::Pistol,
+            state: LifeState::Alive,
+            pain_cooldown: 0,
+            damage_flash: 0.0,
+            bonus_flash: 0.0,
+            keys: 0,
+            fire_cooldown: 0,
+            noclip: false,
+            god: false,
+        }
     }
 
-    pub fn update(&mut self, keys: &HashSet<KeyCode>, map: &crate::map::Map) {
+    pub fn give_key(&mut self, key: Key) {
+        self.keys |= key.bit();
+    }
+
+    pub fn has_key(&self, key: Key) -> bool {
+        self.keys & key.bit() != 0
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.state == LifeState::Alive
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.state == LifeState::GameOver
+    }
+
+    /// Applies `amount` damage, scaled for `difficulty`, a third of which is soaked up by armour.
+    /// Returns false if the hit was ignored because of the pain cooldown or death.
+    pub fn damage(&mut self, amount: i32, difficulty: crate::difficulty::Difficulty) -> bool {
+        if !self.is_alive() || self.god || self.pain_cooldown > 0 || amount <= 0 {
+            return false;
+        }
+        let amount = difficulty.scale_damage(amount);
+        let absorbed = (amount / 3).min(self.armor);
+        self.armor -= absorbed;
+        self.health -= amount - absorbed;
+        self.pain_cooldown = PAIN_COOLDOWN;
+        self.damage_flash = (self.damage_flash + amount as f32 / 40.0).min(1.0);
+        if self.health <= 0 {
+            self.health = 0;
+            self.state = LifeState::Dying { ticks: 0 };
+        }
+        true
+    }
+
+    /// Tile directly in front of the player.
+    pub fn facing_tile(&self) -> (i32, i32) {
+        let tx = (self.x + self.angle.cos()).floor() as i32;
+        let ty = (self.y + self.angle.sin()).floor() as i32;
+        (tx, ty)
+    }
+
+    /// Operates whatever is in front of the player. Locked doors need their key.
+    pub fn use_front(&self, map: &mut crate::map::Map, doors: &mut Entities<Door>) -> UseOutcome {
+        if !self.is_alive() {
+            return UseOutcome::Nothing;
+        }
+        let (tx, ty) = self.facing_tile();
+        // Push walls slide along whichever axis the player faces most directly.
+        let (c, s) = (self.angle.cos(), self.angle.sin());
+        let dir = if c.abs() > s.abs() { (c.signum() as i32, 0) } else { (0, s.signum() as i32) };
+        if map.push_wall(doors, tx, ty, dir) {
+            return UseOutcome::Secret;
+        }
+        if let crate::map::Cell::Exit(kind) = map.cell_at(tx, ty) {
+            return UseOutcome::Exit(kind);
+        }
+        let Some(door) = map.door_at_mut(doors, tx, ty) else {
+            return UseOutcome::Nothing;
+        };
+        if let Some(key) = door.kind.required_key() {
+            if !self.has_key(key) {
+                return UseOutcome::Locked(key);
+            }
+        }
+        door.activate();
+        UseOutcome::Door
+    }
+
+    /// Fires the current weapon if it is ready, returning what was fired.
+    /// Guns fall back to the knife when out of ammo.
+    pub fn fire(&mut self) -> Option<Weapon> {
+        if !self.is_alive() || self.fire_cooldown > 0 {
+            return None;
+        }
+        let weapon = if self.ammo == 0 { Weapon::Knife } else { self.weapon };
+        if weapon != Weapon::Knife {
+            self.ammo -= 1;
+        }
+        self.fire_cooldown = weapon.cooldown();
+        Some(weapon)
+    }
+
+    /// The nearest live enemy straight ahead within `range`, not hidden behind a wall.
+    pub fn aim_target(&self, map: &crate::map::Map, doors: &Entities<Door>, enemies: &Entities<Enemy>, range: f32) -> Option<EntityId> {
+        const HIT_RADIUS: f32 = 0.35;
+        let (dir_x, dir_y) = (self.angle.cos(), self.angle.sin());
+        let range = map.raycast(doors, (self.x, self.y), (dir_x, dir_y), range).map_or(range, |hit| hit.distance);
+        enemies
+            .iter()
+            .filter(|(_, e)| e.alive)
+            .filter_map(|(i, e)| {
+                let (dx, dy) = (e.x - self.x, e.y - self.y);
+                let along = dx * dir_x + dy * dir_y;
+                let across = (dx * dir_y - dy * dir_x).abs();
+                (along > 0.0 && along < range && across < HIT_RADIUS).then_some((i, along))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+    }
+
+    /// Camera height above the floor; drops towards the floor while dying.
+    pub fn eye_height(&self) -> f32 {
+        match self.state {
+            LifeState::Alive => EYE_HEIGHT,
+            LifeState::Dying { ticks } => {
+                let t = (ticks as f32 / DEATH_DROP_TICKS as f32).min(1.0);
+                EYE_HEIGHT + (DEAD_EYE_HEIGHT - EYE_HEIGHT) * t
+            }
+            LifeState::GameOver => DEAD_EYE_HEIGHT,
+        }
+    }
+
+    /// Fraction the screen has faded to black during the death sequence.
+    pub fn death_fade(&self) -> f32 {
+        match self.state {
+            LifeState::Alive => 0.0,
+            LifeState::Dying { ticks } => ticks as f32 / DEATH_TICKS as f32,
+            LifeState::GameOver => 1.0,
+        }
+    }
+
+    /// Places the player at the start of a fresh level. Stats carry over; keys do not.
+    pub fn enter_level(&mut self, map: &crate::map::Map) {
+        (self.x, self.y, self.angle) = map.player_start;
+        self.keys = 0;
+        self.pain_cooldown = 0;
+        self.damage_flash = 0.0;
+        self.bonus_flash = 0.0;
+    }
+
+    /// Puts the player back at the map spawn with a fresh loadout, keeping score and lives.
+    pub fn respawn(&mut self, map: &crate::map::Map) {
+        let (x, y, angle) = map.player_start;
+        *self = Self { lives: self.lives, score: self.score, ..Self::new(x, y, angle) };
+    }
+
+    fn update_vitals(&mut self, map: &crate::map::Map) {
+        self.pain_cooldown = self.pain_cooldown.saturating_sub(1);
+        self.fire_cooldown = self.fire_cooldown.saturating_sub(1);
+        self.damage_flash = (self.damage_flash - 0.05).max(0.0);
+        self.bonus_flash = (self.bonus_flash - 0.1).max(0.0);
+
+        if let LifeState::Dying { ticks } = self.state {
+            if ticks + 1 < DEATH_TICKS {
+                self.state = LifeState::Dying { ticks: ticks + 1 };
+            } else if self.lives > 0 {
+                self.lives -= 1;
+                self.respawn(map);
+            } else {
+                self.state = LifeState::GameOver;
+            }
+        }
+    }
+
+    /// Applies one tick of input. `move_speed` is in tiles and `rot_speed` in radians per tick.
+    pub fn update(&mut self, input: &TickInput, map: &crate::map::Map, doors: &Entities<Door>, move_speed: f32, rot_speed: f32) {
+        self.update_vitals(map);
+        if !self.is_alive() {
+            return;
+        }
+
         let dx = self.angle.cos();
         let dy = self.angle.sin();
 
-        if keys.contains(&KeyCode::KeyW) || keys.contains(&KeyCode::ArrowUp) {
-            self.try_move(dx * MOVE_SPEED, dy * MOVE_SPEED, map);
+        if input.forward {
+            self.try_move(dx * move_speed, dy * move_speed, map, doors);
         }
-        if keys.contains(&KeyCode::KeyS) || keys.contains(&KeyCode::ArrowDown) {
-            self.try_move(-dx * MOVE_SPEED, -dy * MOVE_SPEED, map);
+        if input.back {
+            self.try_move(-dx * move_speed, -dy * move_speed, map, doors);
         }
-        if keys.contains(&KeyCode::ArrowLeft) || keys.contains(&KeyCode::KeyA) {
-            self.angle -= ROT_SPEED;
+        if input.turn_left {
+            self.angle -= rot_speed;
         }
-        if keys.contains(&KeyCode::ArrowRight) || keys.contains(&KeyCode::KeyD) {
-            self.angle += ROT_SPEED;
+        if input.turn_right {
+            self.angle += rot_speed;
         }
     }
 
-    fn try_move(&mut self, dx: f32, dy: f32, map: &crate::map::Map) {
+    fn try_move(&mut self, dx: f32, dy: f32, map: &crate::map::Map, doors: &Entities<Door>) {
         let nx = self.x + dx;
         let ny = self.y + dy;
+        if self.noclip {
+            use crate::map::{MAP_WIDTH, MAP_HEIGHT};
+            self.x = nx.clamp(0.0, MAP_WIDTH as f32 - 0.01);
+            self.y = ny.clamp(0.0, MAP_HEIGHT as f32 - 0.01);
+            return;
+        }
         let margin = 0.25;
-        if !map.is_solid((nx + margin * dx.signum()) as i32, self.y as i32) {
+        if !map.is_solid(doors, (nx + margin * dx.signum()) as i32, self.y as i32) {
             self.x = nx;
         }
-        if !map.is_solid(self.x as i32, (ny + margin * dy.signum()) as i32) {
+        if !map.is_solid(doors, self.x as i32, (ny + margin * dy.signum()) as i32) {
             self.y = ny;
         }
     }
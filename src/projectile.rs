@@ -0,0 +1,212 @@
+use crate::actor::EnemyHit;
+use crate::entity::EntityId;
+use crate::world::World;
+
+/// Radius of the player and of every enemy when struck by a projectile.
+pub const ACTOR_RADIUS: f32 = 0.3;
+/// Distance after which a projectile that has hit nothing fizzles out.
+pub const MAX_RANGE: f32 = 32.0;
+/// How far a blast is pulled back off the wall it struck, so walls do not hide it from the room.
+const WALL_STANDOFF: f32 = 0.01;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ProjectileKind {
+    Rocket,
+    Fireball,
+    Syringe,
+}
+
+impl ProjectileKind {
+    /// Name used for the projectile in data files such as the bestiary.
+    pub fn name(self) -> &'static str {
+        match self {
+            ProjectileKind::Rocket => "rocket",
+            ProjectileKind::Fireball => "fireball",
+            ProjectileKind::Syringe => "syringe",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "rocket" => ProjectileKind::Rocket,
+            "fireball" => ProjectileKind::Fireball,
+            "syringe" => ProjectileKind::Syringe,
+            _ => return None,
+        })
+    }
+
+    /// Tiles per tick.
+    pub fn speed(self) -> f32 {
+        match self {
+            ProjectileKind::Rocket => 0.25,
+            ProjectileKind::Fireball => 0.15,
+            ProjectileKind::Syringe => 0.2,
+        }
+    }
+
+    /// Blast radius in tiles; 0 for projectiles that only hurt what they strike.
+    pub fn splash_radius(self) -> f32 {
+        match self {
+            ProjectileKind::Rocket => 1.5,
+            ProjectileKind::Fireball => 0.75,
+            ProjectileKind::Syringe => 0.0,
+        }
+    }
+}
+
+/// Who fired a projectile. It can strike only the other side, though its blast hurts everyone.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Owner {
+    Player,
+    Enemy(EntityId),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Projectile {
+    pub kind: ProjectileKind,
+    pub x: f32,
+    pub y: f32,
+    /// Unit direction of flight.
+    pub dx: f32,
+    pub dy: f32,
+    /// Damage to whatever it strikes, and at the centre of its blast.
+    pub damage: i32,
+    pub owner: Owner,
+    pub travelled: f32,
+}
+
+impl Projectile {
+    /// A projectile leaving `from` toward `to`.
+    pub fn aimed(kind: ProjectileKind, owner: Owner, damage: i32, from: (f32, f32), to: (f32, f32)) -> Self {
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let len = (dx * dx + dy * dy).sqrt().max(f32::EPSILON);
+        Self { kind, x: from.0, y: from.1, dx: dx / len, dy: dy / len, damage, owner, travelled: 0.0 }
+    }
+}
+
+/// What a projectile ran into.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Struck {
+    Wall,
+    Enemy(EntityId),
+    Player,
+}
+
+/// A projectile hitting something, with everything its impact and blast hurt.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Detonation {
+    pub kind: ProjectileKind,
+    pub owner: Owner,
+    pub x: f32,
+    pub y: f32,
+    pub struck: Struck,
+    /// Every enemy hurt, with how it took the hit.
+    pub enemies: Vec<(EntityId, EnemyHit)>,
+    /// Damage dealt to the player, before difficulty scaling.
+    pub player_damage: i32,
+}
+
+/// Moves every projectile in `world` one tick, sweeping the whole step against walls and actors
+/// so fast projectiles cannot pass through either. Projectiles that hit something despawn, and
+/// are returned as detonations.
+pub fn update(world: &mut World) -> Vec<Detonation> {
+    let mut flying = std::mem::take(&mut world.projectiles);
+    let mut detonations = Vec::new();
+    flying.retain(|_, p| {
+        let step = p.kind.speed();
+        match sweep(world, p, step) {
+            Some((dist, struck)) => {
+                let back = if struck == Struck::Wall { WALL_STANDOFF } else { 0.0 };
+                let at = (p.x + p.dx * (dist - back), p.y + p.dy * (dist - back));
+                detonations.push(detonate(world, p, at, struck));
+                false
+            }
+            None => {
+                p.x += p.dx * step;
+                p.y += p.dy * step;
+                p.travelled += step;
+                p.travelled < MAX_RANGE
+            }
+        }
+    });
+    world.projectiles = flying;
+    detonations
+}
+
+/// Nearest thing the projectile meets within `step`, and how far along it is.
+fn sweep(world: &World, p: &Projectile, step: f32) -> Option<(f32, Struck)> {
+    let player = &world.player;
+    let wall = world.map.raycast(&world.doors, (p.x, p.y), (p.dx, p.dy), step).map(|hit| {
+        let (hx, hy) = hit.point;
+        (((hx - p.x).powi(2) + (hy - p.y).powi(2)).sqrt(), Struck::Wall)
+    });
+    let targets: Vec<((f32, f32), Struck)> = match p.owner {
+        Owner::Player => world.enemies.iter()
+            .filter(|(_, e)| e.alive)
+            .map(|(id, e)| ((e.x, e.y), Struck::Enemy(id)))
+            .collect(),
+        Owner::Enemy(_) if player.is_alive() => vec![((player.x, player.y), Struck::Player)],
+        Owner::Enemy(_) => Vec::new(),
+    };
+    targets.into_iter()
+        .filter_map(|(centre, struck)| Some((enter_circle(p, centre, ACTOR_RADIUS)?, struck)))
+        .filter(|&(t, _)| t <= step)
+        .chain(wall)
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+}
+
+/// Distance along the projectile's path to where it enters the circle, 0 if it starts inside.
+fn enter_circle(p: &Projectile, (cx, cy): (f32, f32), radius: f32) -> Option<f32> {
+    let (fx, fy) = (p.x - cx, p.y - cy);
+    let b = fx * p.dx + fy * p.dy;
+    let c = fx * fx + fy * fy - radius * radius;
+    if c <= 0.0 {
+        return Some(0.0);
+    }
+    let disc = b * b - c;
+    if disc < 0.0 {
+        return None;
+    }
+    let t = -b - disc.sqrt();
+    (t >= 0.0).then_some(t)
+}
+
+/// Applies the direct hit, then the blast: everything within the splash radius that the blast
+/// can see takes damage falling off linearly from the centre. Walls and closed doors shelter.
+fn detonate(world: &mut World, p: &Projectile, (x, y): (f32, f32), struck: Struck) -> Detonation {
+    let mut enemies = Vec::new();
+    let mut player_damage = 0;
+    match struck {
+        Struck::Enemy(id) => enemies.push((id, world.damage_enemy(id, p.damage))),
+        Struck::Player => player_damage += p.damage,
+        Struck::Wall => {}
+    }
+
+    let radius = p.kind.splash_radius();
+    let blast = |world: &World, (ax, ay): (f32, f32)| {
+        let d = ((ax - x).powi(2) + (ay - y).powi(2)).sqrt();
+        if radius <= 0.0 || d >= radius || !world.map.line_of_sight(&world.doors, (x, y), (ax, ay)) {
+            return 0;
+        }
+        (p.damage as f32 * (1.0 - d / radius)).round() as i32
+    };
+    for id in world.enemies.ids() {
+        let e = &world.enemies[id];
+        if !e.alive || struck == Struck::Enemy(id) {
+            continue;
+        }
+        let damage = blast(world, (e.x, e.y));
+        if damage > 0 {
+            enemies.push((id, world.damage_enemy(id, damage)));
+        }
+    }
+    let player = &world.player;
+    if player.is_alive() && struck != Struck::Player {
+        player_damage += blast(world, (player.x, player.y));
+    }
+    if player_damage > 0 {
+        world.player.damage(player_damage, world.difficulty);
+    }
+
+    Detonation { kind: p.kind, owner: p.owner, x, y, struck, enemies, player_damage }
+}
@@ -0,0 +1,51 @@
+/// Tiles gained per tick while a push wall slides.
+pub const PUSHWALL_SPEED: f32 = 1.0 / 64.0;
+/// How far a push wall travels once pushed.
+pub const PUSHWALL_DISTANCE: f32 = 2.0;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PushWallState {
+    Idle,
+    Moving,
+    Done,
+}
+
+/// A secret `Wall(n)` tile that slides away when used.
+#[derive(Clone, Debug)]
+pub struct PushWall {
+    /// Tile the wall started on.
+    pub x: i32,
+    pub y: i32,
+    pub wall: u8,
+    pub dir: (i32, i32),
+    /// Tiles travelled so far, 0.0 to `PUSHWALL_DISTANCE`.
+    pub offset: f32,
+    pub state: PushWallState,
+}
+
+impl PushWall {
+    pub fn new(x: i32, y: i32, wall: u8) -> Self {
+        Self { x, y, wall, dir: (0, 0), offset: 0.0, state: PushWallState::Idle }
+    }
+
+    pub fn is_moving(&self) -> bool {
+        self.state == PushWallState::Moving
+    }
+
+    /// Tile the block is leaving; the block also overlaps the next tile along `dir`.
+    pub fn tile(&self) -> (i32, i32) {
+        let steps = self.offset.floor() as i32;
+        (self.x + self.dir.0 * steps, self.y + self.dir.1 * steps)
+    }
+
+    /// Top-left corner of the block in world space.
+    pub fn block_origin(&self) -> (f32, f32) {
+        (self.x as f32 + self.dir.0 as f32 * self.offset, self.y as f32 + self.dir.1 as f32 * self.offset)
+    }
+
+    /// True if the sliding block overlaps tile `(x, y)`.
+    pub fn covers(&self, x: i32, y: i32) -> bool {
+        let (tx, ty) = self.tile();
+        (x, y) == (tx, ty) || (x, y) == (tx + self.dir.0, ty + self.dir.1)
+    }
+}
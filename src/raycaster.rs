@@ -1,43 +1,97 @@
-use crate::map::{Map, Cell};
+use crate::door::{Door, DoorKind};
+use crate::entity::Entities;
+use crate::map::{Map, Cell, ExitKind};
 
 pub const SCREEN_W: usize = 640;
 pub const SCREEN_H: usize = 480;
+/// Height of the status bar along the bottom of the screen.
+pub const STATUS_BAR_H: usize = 80;
+/// Height of the 3D viewport, which sits above the status bar.
+pub const VIEW_H: usize = SCREEN_H - STATUS_BAR_H;
+/// Default column count; the `r_resolution` cvar overrides it.
 pub const NUM_RAYS: usize = SCREEN_W;
+/// Default field of view; the `fov` cvar overrides it.
 pub const FOV: f32 = std::f32::consts::PI / 3.0;
-pub const HALF_FOV: f32 = FOV / 2.0;
 
 #[allow(dead_code)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub struct RayHit {
     pub distance: f32,
     pub cell: Cell,
     pub side: Side,
     pub wall_x: f32,
+    pub map_x: i32,
+    pub map_y: i32,
+    /// World position the ray struck.
+    pub point: (f32, f32),
+    /// Unit normal of the face that was struck, pointing back toward the ray origin.
+    pub normal: (f32, f32),
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum Side {
     Vertical,
     Horizontal,
 }
 
-pub fn cast_rays(px: f32, py: f32, angle: f32, map: &Map) -> Vec<RayHit> {
-    let mut hits = Vec::with_capacity(NUM_RAYS);
+/// Casts `num_rays` columns spread evenly across a `fov`-radian view cone.
+pub fn cast_rays(px: f32, py: f32, angle: f32, fov: f32, map: &Map, doors: &Entities<Door>, num_rays: usize) -> Vec<RayHit> {
+    #[cfg(not(target_arch = "wasm32"))]
+    { cast_rays_parallel(px, py, angle, fov, map, doors, num_rays) }
+    #[cfg(target_arch = "wasm32")]
+    { cast_rays_serial(px, py, angle, fov, map, doors, num_rays) }
+}
+
+pub fn ray_angle(angle: f32, fov: f32, i: usize, num_rays: usize) -> f32 {
+    angle - fov / 2.0 + (i as f32 / num_rays as f32) * fov
+}
+
+/// Casts `num_rays` columns one after another. Used on wasm, where there are no threads.
+pub fn cast_rays_serial(px: f32, py: f32, angle: f32, fov: f32, map: &Map, doors: &Entities<Door>, num_rays: usize) -> Vec<RayHit> {
+    let mut hits = Vec::with_capacity(num_rays);
 
-    for i in 0..NUM_RAYS {
-        let ray_angle = angle - HALF_FOV + (i as f32 / NUM_RAYS as f32) * FOV;
-        let hit = dda(px, py, ray_angle, map);
+    for i in 0..num_rays {
+        let hit = dda(px, py, ray_angle(angle, fov, i, num_rays), map, doors);
         hits.push(hit);
     }
 
     hits
 }
 
-fn dda(px: f32, py: f32, angle: f32, map: &Map) -> RayHit {
-    let dir_x = angle.cos();
-    let dir_y = angle.sin();
+/// Casts `num_rays` columns across the rayon thread pool.
+/// Every column is independent, so the output matches `cast_rays_serial` exactly.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn cast_rays_parallel(px: f32, py: f32, angle: f32, fov: f32, map: &Map, doors: &Entities<Door>, num_rays: usize) -> Vec<RayHit> {
+    use rayon::prelude::*;
 
-    let mut map_x = px as i32;
-    let mut map_y = py as i32;
+    (0..num_rays)
+        .into_par_iter()
+        .with_min_len(64)
+        .map(|i| dda(px, py, ray_angle(angle, fov, i, num_rays), map, doors))
+        .collect()
+}
+
+fn dda(px: f32, py: f32, angle: f32, map: &Map, doors: &Entities<Door>) -> RayHit {
+    // The map edge always counts as wall, so an unbounded ray always hits something.
+    trace((px, py), (angle.cos(), angle.sin()), f32::INFINITY, map, doors, |_, _| {}).expect("ray left the map")
+}
+
+/// Walks the ray from `origin` along the unit vector `dir` tile by tile, calling
+/// `visit` for the starting tile and every tile entered, up to the first wall, closed part of a
+/// door or sliding push wall. `None` if nothing is hit within `max_dist`.
+///
+/// The renderer's columns and the `Map` visibility queries all go through here.
+pub fn trace(
+    (px, py): (f32, f32),
+    (dir_x, dir_y): (f32, f32),
+    max_dist: f32,
+    map: &Map,
+    doors: &Entities<Door>,
+    mut visit: impl FnMut(i32, i32),
+) -> Option<RayHit> {
+    let mut map_x = px.floor() as i32;
+    let mut map_y = py.floor() as i32;
+    visit(map_x, map_y);
 
     let delta_dist_x = if dir_x == 0.0 { f32::INFINITY } else { (1.0 / dir_x).abs() };
     let delta_dist_y = if dir_y == 0.0 { f32::INFINITY } else { (1.0 / dir_y).abs() };
@@ -54,48 +108,158 @@ fn dda(px: f32, py: f32, angle: f32, map: &Map) -> RayHit {
         (1i32, (map_y as f32 + 1.0 - py) * delta_dist_y)
     };
 
+    let normal = |side| match side {
+        Side::Vertical => (-dir_x.signum(), 0.0),
+        Side::Horizontal => (0.0, -dir_y.signum()),
+    };
+
+    // A sliding push wall is a unit block between tiles; intersect it directly. Only a hit
+    // before the ray leaves tile `(x, y)` counts: the block's other tile is checked on its own,
+    // and a hit further on may lie behind a wall the ray meets first.
+    let pushwall_hit = |x: i32, y: i32, exit: f32| {
+        let pw = map.moving_pushwall_at(x, y)?;
+        let (bx, by) = pw.block_origin();
+        let (distance, side, along) = block_hit(px, py, dir_x, dir_y, bx, by).filter(|hit| hit.0 <= exit)?;
+        Some((distance <= max_dist).then(|| RayHit {
+            distance: distance.max(0.001),
+            cell: Cell::Wall(pw.wall),
+            side,
+            wall_x: along,
+            map_x: x,
+            map_y: y,
+            point: (px + distance * dir_x, py + distance * dir_y),
+            normal: normal(side),
+        }))
+    };
+
+    // The block can be sliding through the tile the ray starts in.
+    if let Some(hit) = pushwall_hit(map_x, map_y, side_dist_x.min(side_dist_y)) {
+        return hit;
+    }
+
     let mut side;
     let mut cell;
+    let mut door_hit = None;
 
     loop {
+        let crossing;
         if side_dist_x < side_dist_y {
+            crossing = side_dist_x;
             side_dist_x += delta_dist_x;
             map_x += step_x;
             side = Side::Vertical;
         } else {
+            crossing = side_dist_y;
             side_dist_y += delta_dist_y;
             map_y += step_y;
             side = Side::Horizontal;
         }
+        if crossing > max_dist {
+            return None;
+        }
+        visit(map_x, map_y);
 
         cell = map.cell_at(map_x, map_y);
-        if cell != Cell::Empty {
-            break;
+        match cell {
+            Cell::Empty => {
+                if let Some(hit) = pushwall_hit(map_x, map_y, side_dist_x.min(side_dist_y)) {
+                    return hit;
+                }
+            }
+            Cell::Wall(_) | Cell::Exit(_) => break,
+            Cell::Door(_) => {
+                // Doors sit recessed half a tile in, facing the way the ray entered.
+                // The ray hits the slab if it reaches that plane before leaving the
+                // tile and lands on the part not yet slid open.
+                let (plane, exit) = match side {
+                    Side::Vertical => (side_dist_x - delta_dist_x * 0.5, side_dist_y),
+                    Side::Horizontal => (side_dist_y - delta_dist_y * 0.5, side_dist_x),
+                };
+                if plane < exit {
+                    let along = match side {
+                        Side::Vertical => py + plane * dir_y,
+                        Side::Horizontal => px + plane * dir_x,
+                    };
+                    let along = along - along.floor();
+                    let open = map.door_open(doors, map_x, map_y);
+                    if along >= open {
+                        door_hit = Some((plane, along - open));
+                        break;
+                    }
+                }
+            }
         }
     }
 
-    let perp_wall_dist = match side {
-        Side::Vertical => side_dist_x - delta_dist_x,
-        Side::Horizontal => side_dist_y - delta_dist_y,
+    let perp_wall_dist = match (door_hit, side) {
+        (Some((plane, _)), _) => plane,
+        (None, Side::Vertical) => side_dist_x - delta_dist_x,
+        (None, Side::Horizontal) => side_dist_y - delta_dist_y,
     };
 
-    let wall_x = match side {
-        Side::Vertical => py + perp_wall_dist * dir_y,
-        Side::Horizontal => px + perp_wall_dist * dir_x,
+    let wall_x = match (door_hit, side) {
+        (Some((_, along)), _) => along,
+        (None, Side::Vertical) => py + perp_wall_dist * dir_y,
+        (None, Side::Horizontal) => px + perp_wall_dist * dir_x,
     };
     let wall_x = wall_x - wall_x.floor();
 
-    RayHit {
+    (perp_wall_dist <= max_dist).then(|| RayHit {
         distance: perp_wall_dist.max(0.001),
         cell,
         side,
         wall_x,
+        map_x,
+        map_y,
+        point: (px + perp_wall_dist * dir_x, py + perp_wall_dist * dir_y),
+        normal: normal(side),
+    })
+}
+
+/// True if `(x, y)` falls inside the `fov` view cone of a camera at `(px, py)` facing `angle`
+/// and nearer than the wall its column hit.
+pub fn point_visible(px: f32, py: f32, angle: f32, fov: f32, hits: &[RayHit], x: f32, y: f32) -> bool {
+    use std::f32::consts::TAU;
+
+    let (dx, dy) = (x - px, y - py);
+    let rel = (dy.atan2(dx) - angle + fov / 2.0).rem_euclid(TAU);
+    if rel >= fov || hits.is_empty() {
+        return false;
+    }
+    let column = ((rel / fov) * hits.len() as f32) as usize;
+    let column = column.min(hits.len() - 1);
+    (dx * dx + dy * dy).sqrt() < hits[column].distance
+}
+
+/// Ray against the unit block with top-left corner `(bx, by)`, by the slab method.
+/// Returns the entry distance, the face that was hit and the offset along that face.
+fn block_hit(px: f32, py: f32, dir_x: f32, dir_y: f32, bx: f32, by: f32) -> Option<(f32, Side, f32)> {
+    let slab = |p: f32, d: f32, lo: f32| -> Option<(f32, f32)> {
+        if d == 0.0 {
+            return if p < lo || p > lo + 1.0 { None } else { Some((f32::NEG_INFINITY, f32::INFINITY)) };
+        }
+        let (a, b) = ((lo - p) / d, (lo + 1.0 - p) / d);
+        Some((a.min(b), a.max(b)))
+    };
+    let (tx0, tx1) = slab(px, dir_x, bx)?;
+    let (ty0, ty1) = slab(py, dir_y, by)?;
+    let enter = tx0.max(ty0);
+    let exit = tx1.min(ty1);
+    if enter > exit || exit < 0.0 {
+        return None;
     }
+    let t = enter.max(0.0);
+    let (side, along) = if tx0 > ty0 {
+        (Side::Vertical, py + t * dir_y - by)
+    } else {
+        (Side::Horizontal, px + t * dir_x - bx)
+    };
+    Some((t, side, along.clamp(0.0, 1.0)))
 }
 
 pub fn compute_column_height(distance: f32) -> u32 {
-    let h = (SCREEN_H as f32 / distance) as u32;
-    h.min(SCREEN_H as u32)
+    let h = (VIEW_H as f32 / distance) as u32;
+    h.min(VIEW_H as u32)
 }
 
 pub fn wall_color(cell: &Cell, side: &Side) -> [f32; 4] {
@@ -103,7 +267,12 @@ pub fn wall_color(cell: &Cell, side: &Side) -> [f32; 4] {
         Cell::Wall(1) => [0.6, 0.6, 0.6, 1.0],
         Cell::Wall(2) => [0.7, 0.4, 0.2, 1.0],
         Cell::Wall(3) => [0.4, 0.4, 0.6, 1.0],
-        Cell::Door    => [0.6, 0.5, 0.1, 1.0],
+        Cell::Door(DoorKind::Normal)       => [0.6, 0.5, 0.1, 1.0],
+        Cell::Door(DoorKind::GoldLocked)   => [0.85, 0.7, 0.1, 1.0],
+        Cell::Door(DoorKind::SilverLocked) => [0.6, 0.65, 0.75, 1.0],
+        Cell::Door(DoorKind::Elevator)     => [0.3, 0.5, 0.5, 1.0],
+        Cell::Exit(ExitKind::Normal)       => [0.2, 0.6, 0.2, 1.0],
+        Cell::Exit(ExitKind::Secret)       => [0.55, 0.6, 0.35, 1.0],
         _             => [0.5, 0.5, 0.5, 1.0],
     };
     if *side == Side::Horizontal {
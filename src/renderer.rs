@@ -1,8 +1,27 @@
 use wgpu::util::DeviceExt;
 use bytemuck::{Pod, Zeroable};
-use crate::raycaster::{SCREEN_W, SCREEN_H, cast_rays, compute_column_height, wall_color};
-use crate::map::{Map, MAP_WIDTH, MAP_HEIGHT};
+use crate::raycaster::{SCREEN_W, SCREEN_H, VIEW_H, STATUS_BAR_H, RayHit, compute_column_height, point_visible, ray_angle, wall_color};
+use crate::map::{Cell, ExitKind, MAP_WIDTH, MAP_HEIGHT};
+use crate::door::DoorKind;
+use crate::items::ItemKind;
+use crate::light::apply_light;
+use crate::automap::Automap;
+use crate::font::{self, ADVANCE, GLYPH_H};
+use crate::hud::StatusBar;
+use crate::sprite::{Sprite, enemy_sprite, item_sprite, projectile_sprite};
 use crate::player::Player;
+use crate::world::World;
+use crate::episode::{LevelStats, format_time};
+use crate::menu::MenuView;
+use crate::console::ConsoleView;
+use crate::minimap::{MinimapConfig, MinimapEnemies};
+
+const FLOOR_BAND_H: f32 = 4.0;
+const FLOOR_BLOCK_W: usize = 8;
+const MINIMAP_CONE_STEP: usize = 8;
+const STATUS_BAR_BG: [f32; 4] = [0.0, 0.25, 0.4, 1.0];
+const STATUS_TEXT: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const STATUS_LABEL: [f32; 4] = [0.6, 0.8, 1.0, 1.0];
 
 #[repr(C)]
 #[derive(Copy, Clone, Pod, Zeroable)]
@@ -32,12 +51,30 @@ impl Vertex {
     }
 }
 
+/// Everything a frame is drawn from, borrowed from the game state for the duration of `render`.
+pub struct Scene<'a> {
+    pub world: &'a World,
+    pub hits: &'a [RayHit],
+    /// Field of view, in radians, the hits were cast with.
+    pub fov: f32,
+    pub ceiling_color: [f32; 4],
+    pub floor_color: [f32; 4],
+    pub automap: &'a Automap,
+    pub status: &'a StatusBar,
+    pub message: Option<&'a str>,
+    /// End-of-level stats; replaces the 3D view while shown.
+    pub intermission: Option<&'a LevelStats>,
+    pub menu: Option<&'a MenuView>,
+    pub console: Option<ConsoleView<'a>>,
+}
+
 pub struct Renderer {
     pub surface: wgpu::Surface<'static>,
     pub device: wgpu::Device,
     pub queue: wgpu::Queue,
     pub config: wgpu::SurfaceConfiguration,
     pipeline: wgpu::RenderPipeline,
+    pub minimap: MinimapConfig,
 }
 
 impl Renderer {
@@ -129,7 +166,7 @@ impl Renderer {
                 entry_point: Some("fs_main"),
                 targets: &[Some(wgpu::ColorTargetState {
                     format,
-                    blend: Some(wgpu::BlendState::REPLACE),
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
                     write_mask: wgpu::ColorWrites::ALL,
                 })],
                 compilation_options: Default::default(),
@@ -144,7 +181,7 @@ impl Renderer {
             cache: None,
         });
 
-        Self { surface, device, queue, config, pipeline }
+        Self { surface, device, queue, config, pipeline, minimap: MinimapConfig::default() }
     }
 
     pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
@@ -155,14 +192,37 @@ impl Renderer {
         }
     }
 
-    pub fn render(&self, player: &Player, map: &Map) -> Result<(), wgpu::SurfaceError> {
+    pub fn render(&self, scene: &Scene) -> Result<(), wgpu::SurfaceError> {
         let output = self.surface.get_current_texture()?;
         let view = output.texture.create_view(&wgpu::TextureViewDescriptor::default());
 
         let mut vertices: Vec<Vertex> = Vec::new();
 
-        build_3d_view(&mut vertices, player, map);
-        build_minimap(&mut vertices, player, map);
+        if let Some(stats) = scene.intermission {
+            build_intermission(&mut vertices, stats);
+            build_status_bar(&mut vertices, scene.status, scene.world.lighting.tick);
+        } else if scene.automap.open {
+            build_automap(&mut vertices, scene.world, scene.automap);
+        } else {
+            build_3d_view(&mut vertices, scene);
+            build_sprites(&mut vertices, scene);
+            build_minimap(&mut vertices, scene, &self.minimap);
+            build_view_tint(&mut vertices, &scene.world.player);
+            if let Some(message) = scene.message {
+                push_text_centered(&mut vertices, message, SCREEN_W as f32 / 2.0, 12.0, 2.0, STATUS_TEXT);
+            }
+            build_status_bar(&mut vertices, scene.status, scene.world.lighting.tick);
+        }
+
+        if let Some(menu) = scene.menu {
+            build_menu(&mut vertices, menu);
+        }
+        if let Some(console) = &scene.console {
+            build_console(&mut vertices, console);
+        }
+
+        let [r, g, b] = scene.world.map.fog.color;
+        let clear_color = wgpu::Color { r: r as f64, g: g as f64, b: b as f64, a: 1.0 };
 
         let vbuf = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: None,
@@ -179,7 +239,7 @@ impl Renderer {
                     resolve_target: None,
                     depth_slice: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color { r: 0.02, g: 0.02, b: 0.02, a: 1.0 }),
+                        load: wgpu::LoadOp::Clear(clear_color),
                         store: wgpu::StoreOp::Store,
                     },
                 })],
@@ -214,63 +274,534 @@ fn push_quad(verts: &mut Vec<Vertex>, x0: f32, y0: f32, x1: f32, y1: f32, color:
     verts.extend_from_slice(&[tl, bl, tr, tr, bl, br]);
 }
 
-fn build_3d_view(verts: &mut Vec<Vertex>, player: &Player, map: &Map) {
+fn push_tri(verts: &mut Vec<Vertex>, a: [f32; 2], b: [f32; 2], c: [f32; 2], color: [f32; 4]) {
+    verts.extend_from_slice(&[
+        Vertex { position: a, color },
+        Vertex { position: b, color },
+        Vertex { position: c, color },
+    ]);
+}
+
+/// Pushes an arbitrary convex quad given its corners in winding order.
+fn push_poly4(verts: &mut Vec<Vertex>, p: [[f32; 2]; 4], color: [f32; 4]) {
+    push_tri(verts, p[0], p[1], p[2], color);
+    push_tri(verts, p[0], p[2], p[3], color);
+}
+
+fn build_3d_view(verts: &mut Vec<Vertex>, scene: &Scene) {
+    let Scene { world, hits, fov, .. } = *scene;
+    let World { player, map, lighting, .. } = world;
     let sw = SCREEN_W as f32;
     let sh = SCREEN_H as f32;
+    let vh = VIEW_H as f32;
 
-    let ceiling_color = [0.15, 0.15, 0.25, 1.0f32];
-    let floor_color   = [0.25, 0.20, 0.15, 1.0f32];
-    push_quad(verts, -1.0, 0.0, 1.0, 1.0, ceiling_color);
-    push_quad(verts, -1.0, -1.0, 1.0, 0.0, floor_color);
-
-    let hits = cast_rays(player.x, player.y, player.angle, map);
+    build_floor_ceiling(verts, scene);
 
+    // Columns are stretched to fill the screen when fewer rays than pixels were cast.
+    let col_w = sw / hits.len().max(1) as f32;
+    // The wall stands `eye` below the camera and `1 - eye` above it.
+    let eye = player.eye_height();
     for (i, hit) in hits.iter().enumerate() {
         let col_h = compute_column_height(hit.distance) as f32;
-        let top    = (sh / 2.0) - (col_h / 2.0);
-        let bottom = (sh / 2.0) + (col_h / 2.0);
+        let top    = (vh / 2.0) - col_h * (1.0 - eye);
+        let bottom = (vh / 2.0) + col_h * eye;
 
-        let x0 = ndc_x(i as f32, sw);
-        let x1 = ndc_x(i as f32 + 1.0, sw);
-        let y0 = ndc_y(bottom.min(sh), sh);
+        let x0 = ndc_x(i as f32 * col_w, sw);
+        let x1 = ndc_x((i + 1) as f32 * col_w, sw);
+        let y0 = ndc_y(bottom.min(vh), sh);
         let y1 = ndc_y(top.max(0.0), sh);
 
-        let color = wall_color(&hit.cell, &hit.side);
+        // Light the wall from just in front of it so it takes the sector the player sees it from.
+        let a = ray_angle(player.angle, fov, i, hits.len());
+        let d = hit.distance - 0.01;
+        let light = lighting.light_at(map, player.x + a.cos() * d, player.y + a.sin() * d);
+        let color = apply_light(wall_color(&hit.cell, &hit.side), light);
+        let color = map.fog.apply(color, hit.distance);
         push_quad(verts, x0, y0, x1, y1, color);
     }
 }
 
-fn build_minimap(verts: &mut Vec<Vertex>, player: &Player, map: &Map) {
-    let scale = 0.012f32;
-    let ox = -1.0f32;
-    let oy = -1.0f32;
+/// Draws every billboard sprite back to front, clipped per column against the wall hits.
+fn build_sprites(verts: &mut Vec<Vertex>, scene: &Scene) {
+    let World { player, items, enemies, projectiles, bestiary, .. } = scene.world;
+
+    let items = items.values().filter(|i| !i.picked).map(|i| item_sprite(i.kind, i.x, i.y));
+    let enemies = enemies.values().filter(|e| e.alive).map(|e| enemy_sprite(bestiary.get(e.kind), e.x, e.y));
+    let projectiles = projectiles.values().map(projectile_sprite);
+    let mut sprites: Vec<(f32, Sprite)> = items
+        .chain(enemies)
+        .chain(projectiles)
+        .map(|s| (((s.x - player.x).powi(2) + (s.y - player.y).powi(2)).sqrt(), s))
+        .collect();
+    sprites.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    for (dist, sprite) in &sprites {
+        build_sprite(verts, scene, sprite, *dist);
+    }
+}
+
+fn build_sprite(verts: &mut Vec<Vertex>, scene: &Scene, sprite: &Sprite, dist: f32) {
+    use std::f32::consts::{PI, TAU};
+
+    let Scene { world, hits, fov, .. } = *scene;
+    let World { player, map, lighting, .. } = world;
+    if dist < 0.2 || hits.is_empty() {
+        return;
+    }
+    let sw = SCREEN_W as f32;
+    let sh = SCREEN_H as f32;
+    let vh = VIEW_H as f32;
+
+    let rel = ((sprite.y - player.y).atan2(sprite.x - player.x) - player.angle + PI).rem_euclid(TAU) - PI;
+    let center_x = (rel + fov / 2.0) / fov * sw;
+    // Same projection as the walls: a full wall is `VIEW_H / dist` pixels tall.
+    let wall_h = vh / dist;
+    let size_px = wall_h * sprite.size;
+    let left = center_x - size_px / 2.0;
+    let top = vh / 2.0 + wall_h * player.eye_height() - size_px;
+    if left + size_px < 0.0 || left > sw {
+        return;
+    }
+
+    let light = lighting.light_at(map, sprite.x, sprite.y);
+    let column_visible = |px: usize| {
+        let i = (px * hits.len() / SCREEN_W).min(hits.len() - 1);
+        hits[i].distance > dist
+    };
+
+    for layer in sprite.layers {
+        let x0 = (left + layer.x0 * size_px).max(0.0);
+        let x1 = (left + layer.x1 * size_px).min(sw);
+        let y0 = (top + layer.y0 * size_px).max(0.0);
+        let y1 = (top + layer.y1 * size_px).min(vh);
+        if x1 <= x0 || y1 <= y0 {
+            continue;
+        }
+        let color = map.fog.apply(apply_light(layer.color(sprite), light), dist);
+
+        // Emit one quad per run of columns where the sprite is nearer than the wall.
+        let mut px = x0.floor() as usize;
+        let end = (x1.ceil() as usize).min(SCREEN_W);
+        while px < end {
+            if !column_visible(px) {
+                px += 1;
+                continue;
+            }
+            let start = px;
+            while px < end && column_visible(px) {
+                px += 1;
+            }
+            let rx0 = x0.max(start as f32);
+            let rx1 = x1.min(px as f32);
+            push_quad(verts, ndc_x(rx0, sw), ndc_y(y1, sh), ndc_x(rx1, sw), ndc_y(y0, sh), color);
+        }
+    }
+}
+
+/// Floor-casts floor and ceiling in blocks of `FLOOR_BLOCK_W` x `FLOOR_BAND_H` pixels,
+/// so fog follows the row distance and lighting follows the world position under each block.
+fn build_floor_ceiling(verts: &mut Vec<Vertex>, scene: &Scene) {
+    let Scene { world, fov, ceiling_color, floor_color, .. } = *scene;
+    let World { player, map, lighting, .. } = world;
+    let sw = SCREEN_W as f32;
+    let sh = SCREEN_H as f32;
+    let half = VIEW_H as f32 / 2.0;
+    let eye = player.eye_height();
+
+    let mut y = 0.0;
+    while y < half {
+        let y_end = (y + FLOOR_BAND_H).min(half);
+        // A row `dy` pixels below the horizon shows the floor where a wall's foot, `eye`
+        // of its column height below the horizon, would land: the inverse of `compute_column_height`.
+        let dy = (y + (y_end - y) / 2.0).max(0.5);
+        let floor_dist = VIEW_H as f32 * eye / dy;
+        let ceiling_dist = VIEW_H as f32 * (1.0 - eye) / dy;
+
+        for col in (0..SCREEN_W).step_by(FLOOR_BLOCK_W) {
+            let col_end = (col + FLOOR_BLOCK_W).min(SCREEN_W);
+            let a = ray_angle(player.angle, fov, (col + col_end) / 2, SCREEN_W);
+            let (dir_x, dir_y) = (a.cos(), a.sin());
+            let x0 = ndc_x(col as f32, sw);
+            let x1 = ndc_x(col_end as f32, sw);
+
+            let light = lighting.light_at(map, player.x + dir_x * ceiling_dist, player.y + dir_y * ceiling_dist);
+            let ceiling = map.fog.apply(apply_light(ceiling_color, light), ceiling_dist);
+            push_quad(verts, x0, ndc_y(half - y, sh), x1, ndc_y(half - y_end, sh), ceiling);
+
+            let light = lighting.light_at(map, player.x + dir_x * floor_dist, player.y + dir_y * floor_dist);
+            let floor = map.fog.apply(apply_light(floor_color, light), floor_dist);
+            push_quad(verts, x0, ndc_y(half + y_end, sh), x1, ndc_y(half + y, sh), floor);
+        }
+
+        y = y_end;
+    }
+}
+
+fn map_tile_color(cell: Cell) -> [f32; 4] {
+    match cell {
+        Cell::Empty    => [0.1, 0.1, 0.1, 0.7],
+        Cell::Wall(1)  => [0.7, 0.7, 0.7, 0.9],
+        Cell::Wall(2)  => [0.7, 0.4, 0.2, 0.9],
+        Cell::Wall(3)  => [0.4, 0.4, 0.7, 0.9],
+        Cell::Door(DoorKind::Normal)       => [0.8, 0.7, 0.1, 0.9],
+        Cell::Door(DoorKind::GoldLocked)   => [1.0, 0.85, 0.0, 0.9],
+        Cell::Door(DoorKind::SilverLocked) => [0.75, 0.8, 0.9, 0.9],
+        Cell::Door(DoorKind::Elevator)     => [0.2, 0.7, 0.7, 0.9],
+        Cell::Exit(ExitKind::Normal)       => [0.1, 0.9, 0.1, 0.9],
+        Cell::Exit(ExitKind::Secret)       => [0.6, 0.7, 0.4, 0.9],
+        _              => [0.5, 0.5, 0.5, 0.9],
+    }
+}
+
+fn build_minimap(verts: &mut Vec<Vertex>, scene: &Scene, cfg: &MinimapConfig) {
+    let Scene { world, hits, fov, .. } = *scene;
+    let World { player, map, .. } = world;
+    let sw = SCREEN_W as f32;
+    let sh = SCREEN_H as f32;
+    let size = cfg.view_tiles as f32 * cfg.tile_px;
+    let (ox, oy) = cfg.scroll(player.x, player.y);
+
+    let to_px = |wx: f32, wy: f32| {
+        (
+            (cfg.x + (wx - ox) * cfg.tile_px).clamp(cfg.x, cfg.x + size),
+            (cfg.y + (wy - oy) * cfg.tile_px).clamp(cfg.y, cfg.y + size),
+        )
+    };
+    let to_ndc = |wx: f32, wy: f32| {
+        let (x, y) = to_px(wx, wy);
+        [ndc_x(x, sw), ndc_y(y, sh)]
+    };
+    let fade = |c: [f32; 4]| [c[0], c[1], c[2], c[3] * cfg.opacity];
+    let rect = |verts: &mut Vec<Vertex>, x0: f32, y0: f32, x1: f32, y1: f32, color: [f32; 4]| {
+        let (px0, py0) = to_px(x0, y0);
+        let (px1, py1) = to_px(x1, y1);
+        if px1 > px0 && py1 > py0 {
+            push_quad(verts, ndc_x(px0, sw), ndc_y(py1, sh), ndc_x(px1, sw), ndc_y(py0, sh), color);
+        }
+    };
+
+    let (col0, row0) = (ox.floor() as usize, oy.floor() as usize);
+    let col1 = (col0 + cfg.view_tiles + 1).min(MAP_WIDTH);
+    let row1 = (row0 + cfg.view_tiles + 1).min(MAP_HEIGHT);
+    for row in row0..row1 {
+        for col in col0..col1 {
+            let color = fade(map_tile_color(map.walls[row][col]));
+            let (x, y) = (col as f32, row as f32);
+            rect(verts, x, y, x + 0.95, y + 0.95, color);
+        }
+    }
+
+    // View cone as a fan through every few ray hits, always including the outermost ray.
+    let cone = fade([1.0, 1.0, 0.0, 0.35]);
+    let hit_point = |i: usize| {
+        let a = ray_angle(player.angle, fov, i, hits.len());
+        let d = hits[i].distance;
+        to_ndc(player.x + a.cos() * d, player.y + a.sin() * d)
+    };
+    let origin = to_ndc(player.x, player.y);
+    let mut prev: Option<[f32; 2]> = None;
+    let last = hits.len().saturating_sub(1);
+    for i in (0..hits.len()).step_by(MINIMAP_CONE_STEP).chain(std::iter::once(last)) {
+        let p = hit_point(i);
+        if let Some(q) = prev {
+            push_tri(verts, origin, q, p, cone);
+        }
+        prev = Some(p);
+    }
+
+    if cfg.enemies != MinimapEnemies::Hidden {
+        for e in world.enemies.values().filter(|e| e.alive) {
+            if cfg.enemies == MinimapEnemies::Visible && !point_visible(player.x, player.y, player.angle, fov, hits, e.x, e.y) {
+                continue;
+            }
+            rect(verts, e.x - 0.3, e.y - 0.3, e.x + 0.3, e.y + 0.3, fade([1.0, 0.4, 1.0, 1.0]));
+        }
+    }
+
+    rect(verts, player.x - 0.4, player.y - 0.4, player.x + 0.4, player.y + 0.4, fade([1.0, 0.0, 0.0, 1.0]));
+}
+
+fn build_automap(verts: &mut Vec<Vertex>, world: &World, automap: &Automap) {
+    let World { player, map, .. } = world;
+    let sw = SCREEN_W as f32;
+    let sh = SCREEN_H as f32;
+
+    push_quad(verts, -1.0, -1.0, 1.0, 1.0, [0.0, 0.0, 0.0, 1.0]);
+
+    let (cx, cy) = if automap.follow { (player.x, player.y) } else { (automap.pan_x, automap.pan_y) };
+    // Rotating by -(angle + 90 deg) turns the player's facing direction to screen-up.
+    let rot = if automap.rotate { -player.angle - std::f32::consts::FRAC_PI_2 } else { 0.0 };
+    let (sin, cos) = rot.sin_cos();
+    let to_ndc = |wx: f32, wy: f32| -> [f32; 2] {
+        let (dx, dy) = (wx - cx, wy - cy);
+        let rx = dx * cos - dy * sin;
+        let ry = dx * sin + dy * cos;
+        [ndc_x(sw / 2.0 + rx * automap.zoom, sw), ndc_y(sh / 2.0 + ry * automap.zoom, sh)]
+    };
+    let tile = |x: f32, y: f32, inset: f32| {
+        [
+            to_ndc(x + inset, y + inset),
+            to_ndc(x + 1.0 - inset, y + inset),
+            to_ndc(x + 1.0 - inset, y + 1.0 - inset),
+            to_ndc(x + inset, y + 1.0 - inset),
+        ]
+    };
 
     for row in 0..MAP_HEIGHT {
         for col in 0..MAP_WIDTH {
-            let color = match map.walls[row][col] {
-                crate::map::Cell::Empty    => [0.1, 0.1, 0.1, 0.7],
-                crate::map::Cell::Wall(1)  => [0.7, 0.7, 0.7, 0.9],
-                crate::map::Cell::Wall(2)  => [0.7, 0.4, 0.2, 0.9],
-                crate::map::Cell::Wall(3)  => [0.4, 0.4, 0.7, 0.9],
-                crate::map::Cell::Door     => [0.8, 0.7, 0.1, 0.9],
-                _                          => [0.5, 0.5, 0.5, 0.9],
-            };
-            let x0 = ox + col as f32 * scale;
-            let y0 = oy + row as f32 * scale;
-            let x1 = x0 + scale * 0.95;
-            let y1 = y0 + scale * 0.95;
-            push_quad(verts, x0, y0, x1, y1, color);
+            if !automap.is_seen(col, row) {
+                continue;
+            }
+            let cell = map.walls[row][col];
+            let (x, y) = (col as f32, row as f32);
+            push_poly4(verts, tile(x, y, 0.03), map_tile_color(cell));
+            if let Cell::Door(kind) = cell {
+                // Locked doors get a marker in their key's colour so they read at any zoom.
+                let marker = match kind {
+                    DoorKind::Normal => [0.3, 0.2, 0.0, 1.0],
+                    DoorKind::GoldLocked => [1.0, 0.6, 0.0, 1.0],
+                    DoorKind::SilverLocked => [1.0, 1.0, 1.0, 1.0],
+                    DoorKind::Elevator => [0.0, 0.3, 0.3, 1.0],
+                };
+                push_poly4(verts, tile(x, y, 0.3), marker);
+            }
+            if let Cell::Exit(_) = cell {
+                push_poly4(verts, tile(x, y, 0.25), [1.0, 1.0, 1.0, 1.0]);
+                push_poly4(verts, tile(x, y, 0.35), [0.0, 0.6, 0.0, 1.0]);
+            }
+        }
+    }
+
+    for item in world.items.values().filter(|i| !i.picked) {
+        let color = match item.kind {
+            ItemKind::GoldKey => [1.0, 0.8, 0.0, 1.0],
+            ItemKind::SilverKey => [0.85, 0.9, 1.0, 1.0],
+            _ => continue,
+        };
+        if automap.is_spotted(item.x as usize, item.y as usize) {
+            let (x, y) = (item.x.floor(), item.y.floor());
+            push_poly4(verts, tile(x, y, 0.35), color);
+        }
+    }
+
+    let (dx, dy) = (player.angle.cos(), player.angle.sin());
+    let tip = to_ndc(player.x + dx * 0.5, player.y + dy * 0.5);
+    let left = to_ndc(player.x - dx * 0.3 + dy * 0.25, player.y - dy * 0.3 - dx * 0.25);
+    let right = to_ndc(player.x - dx * 0.3 - dy * 0.25, player.y - dy * 0.3 + dx * 0.25);
+    push_tri(verts, tip, left, right, [1.0, 0.0, 0.0, 1.0]);
+}
+
+/// Draws `text` with its top-left corner at pixel `(x, y)`, `scale` screen pixels per font pixel.
+/// Each glyph column is emitted as one quad per vertical run of set bits.
+pub fn push_text(verts: &mut Vec<Vertex>, text: &str, x: f32, y: f32, scale: f32, color: [f32; 4]) {
+    let sw = SCREEN_W as f32;
+    let sh = SCREEN_H as f32;
+
+    for (i, c) in text.chars().enumerate() {
+        let gx = x + (i * ADVANCE) as f32 * scale;
+        for (col, bits) in font::glyph(c).iter().enumerate() {
+            let cx = gx + col as f32 * scale;
+            let mut row = 0;
+            while row < GLYPH_H {
+                if bits & (1 << row) == 0 {
+                    row += 1;
+                    continue;
+                }
+                let start = row;
+                while row < GLYPH_H && bits & (1 << row) != 0 {
+                    row += 1;
+                }
+                let y0 = y + start as f32 * scale;
+                let y1 = y + row as f32 * scale;
+                push_quad(verts, ndc_x(cx, sw), ndc_y(y1, sh), ndc_x(cx + scale, sw), ndc_y(y0, sh), color);
+            }
+        }
+    }
+}
+
+fn push_text_centered(verts: &mut Vec<Vertex>, text: &str, cx: f32, y: f32, scale: f32, color: [f32; 4]) {
+    push_text(verts, text, cx - font::text_width(text, scale) / 2.0, y, scale, color);
+}
+
+fn push_rect_px(verts: &mut Vec<Vertex>, x0: f32, y0: f32, x1: f32, y1: f32, color: [f32; 4]) {
+    let sw = SCREEN_W as f32;
+    let sh = SCREEN_H as f32;
+    push_quad(verts, ndc_x(x0, sw), ndc_y(y1, sh), ndc_x(x1, sw), ndc_y(y0, sh), color);
+}
+
+/// Red pain flash and the black death fade, laid over the 3D viewport.
+fn build_view_tint(verts: &mut Vec<Vertex>, player: &Player) {
+    let sw = SCREEN_W as f32;
+    let vh = VIEW_H as f32;
+    if player.damage_flash > 0.0 {
+        push_rect_px(verts, 0.0, 0.0, sw, vh, [0.8, 0.0, 0.0, player.damage_flash * 0.6]);
+    }
+    if player.bonus_flash > 0.0 {
+        push_rect_px(verts, 0.0, 0.0, sw, vh, [1.0, 0.85, 0.2, player.bonus_flash * 0.3]);
+    }
+    let fade = player.death_fade();
+    if fade > 0.0 {
+        push_rect_px(verts, 0.0, 0.0, sw, vh, [0.0, 0.0, 0.0, fade.min(1.0)]);
+    }
+    if player.is_game_over() {
+        push_text_centered(verts, "GAME OVER", sw / 2.0, vh / 2.0 - 20.0, 4.0, [0.8, 0.0, 0.0, 1.0]);
+        push_text_centered(verts, "PRESS ENTER", sw / 2.0, vh / 2.0 + 20.0, 2.0, STATUS_TEXT);
+    }
+}
+
+/// Drop-down console over the top of the view: scrollback with the input line at the bottom.
+fn build_console(verts: &mut Vec<Vertex>, console: &ConsoleView) {
+    let sw = SCREEN_W as f32;
+    let height = (VIEW_H as f32 * 0.6).floor();
+    let scale = 1.0;
+    let line_h = (GLYPH_H as f32 + 3.0) * scale;
+    let margin = 4.0;
+    push_rect_px(verts, 0.0, 0.0, sw, height, [0.05, 0.05, 0.1, 0.85]);
+    push_rect_px(verts, 0.0, height, sw, height + 2.0, [0.6, 0.5, 0.1, 1.0]);
+
+    let input_y = height - margin - line_h;
+    push_text(verts, &format!("] {}_", console.input), margin, input_y, scale, STATUS_TEXT);
+
+    let rows = ((input_y - margin) / line_h) as usize;
+    for (i, line) in console.lines.iter().rev().take(rows).enumerate() {
+        let y = input_y - (i + 1) as f32 * line_h;
+        push_text(verts, line, margin, y, scale, STATUS_LABEL);
+    }
+}
+
+fn build_menu(verts: &mut Vec<Vertex>, menu: &MenuView) {
+    let sw = SCREEN_W as f32;
+    let sh = SCREEN_H as f32;
+    if menu.overlay {
+        push_rect_px(verts, 0.0, 0.0, sw, sh, [0.0, 0.0, 0.0, 0.6]);
+    } else {
+        push_rect_px(verts, 0.0, 0.0, sw, sh, [0.35, 0.0, 0.0, 1.0]);
+    }
+
+    let title_scale = if menu.overlay { 3.0 } else { 5.0 };
+    push_text_centered(verts, menu.title, sw / 2.0, 60.0, title_scale, STATUS_TEXT);
+
+    let line_h = 32.0;
+    let top = 160.0;
+    let width = menu.items.iter().map(|s| font::text_width(s, 2.0)).fold(0.0, f32::max);
+    let x = (sw - width) / 2.0;
+    for (i, item) in menu.items.iter().enumerate() {
+        let y = top + i as f32 * line_h;
+        let color = if i == menu.cursor { [1.0, 0.85, 0.2, 1.0] } else { STATUS_LABEL };
+        if i == menu.cursor {
+            push_rect_px(verts, x - 24.0, y + 2.0, x - 12.0, y + 12.0, color);
+        }
+        push_text(verts, item, x, y, 2.0, color);
+    }
+}
+
+fn build_intermission(verts: &mut Vec<Vertex>, stats: &LevelStats) {
+    let sw = SCREEN_W as f32;
+    let vh = VIEW_H as f32;
+    push_rect_px(verts, 0.0, 0.0, sw, vh, [0.0, 0.25, 0.25, 1.0]);
+
+    let title = if stats.episode_done {
+        "EPISODE COMPLETE".to_string()
+    } else {
+        format!("FLOOR {} COMPLETED", stats.floor)
+    };
+    push_text_centered(verts, &title, sw / 2.0, 40.0, 3.0, STATUS_TEXT);
+
+    let rows = [
+        ("KILL RATIO", format!("{}%", LevelStats::ratio(stats.kills, stats.kills_total))),
+        ("SECRET RATIO", format!("{}%", LevelStats::ratio(stats.secrets, stats.secrets_total))),
+        ("TREASURE RATIO", format!("{}%", LevelStats::ratio(stats.treasure, stats.treasure_total))),
+        ("TIME", format_time(stats.seconds())),
+        ("PAR", format_time(stats.par_secs)),
+    ];
+    let mut y = 120.0;
+    for (label, value) in rows {
+        push_text(verts, label, 140.0, y, 2.0, STATUS_LABEL);
+        push_text(verts, &value, 500.0 - font::text_width(&value, 2.0), y, 2.0, STATUS_TEXT);
+        y += 36.0;
+    }
+
+    push_text_centered(verts, "PRESS ENTER", sw / 2.0, vh - 40.0, 2.0, STATUS_TEXT);
+}
+
+fn build_status_bar(verts: &mut Vec<Vertex>, status: &StatusBar, tick: u32) {
+    let sw = SCREEN_W as f32;
+    let top = VIEW_H as f32;
+    let bottom = top + STATUS_BAR_H as f32;
+    push_rect_px(verts, 0.0, top, sw, bottom, STATUS_BAR_BG);
+
+    let weapon = status.weapon.name();
+    let health = format!("{}%", status.health.max(0));
+    let fields: [(&str, String, f32); 7] = [
+        ("FLOOR", status.floor.to_string(), 64.0),
+        ("SCORE", status.score.to_string(), 136.0),
+        ("LIVES", status.lives.to_string(), 64.0),
+        ("", String::new(), 80.0),
+        ("HEALTH", health, 96.0),
+        ("AMMO", status.ammo.to_string(), 80.0),
+        ("", weapon.to_string(), 120.0),
+    ];
+
+    let mut x = 0.0;
+    for (i, (label, value, w)) in fields.iter().enumerate() {
+        // Sunken panel border, as on the original bar.
+        push_rect_px(verts, x + 2.0, top + 2.0, x + w - 2.0, bottom - 2.0, [0.0, 0.12, 0.22, 1.0]);
+        push_rect_px(verts, x + 4.0, top + 4.0, x + w - 4.0, bottom - 4.0, STATUS_BAR_BG);
+
+        let cx = x + w / 2.0;
+        match i {
+            3 => build_face(verts, x, top, *w, status.health, tick),
+            6 => {
+                let scale = if font::text_width(value, 2.0) <= w - 12.0 { 2.0 } else { 1.0 };
+                let y = top + (STATUS_BAR_H as f32 - GLYPH_H as f32 * scale) / 2.0;
+                push_text_centered(verts, value, cx, y, scale, STATUS_TEXT);
+
+                let keys = [(status.gold_key, [1.0, 0.8, 0.0, 1.0]), (status.silver_key, [0.8, 0.85, 0.9, 1.0])];
+                for (k, (held, color)) in keys.iter().enumerate() {
+                    if *held {
+                        let kx = x + 10.0 + k as f32 * 14.0;
+                        push_rect_px(verts, kx, top + 8.0, kx + 8.0, top + 20.0, *color);
+                    }
+                }
+            }
+            _ => {
+                push_text_centered(verts, label, cx, top + 10.0, 1.0, STATUS_LABEL);
+                push_text_centered(verts, value, cx, top + 32.0, 3.0, STATUS_TEXT);
+            }
+        }
+        if i == 4 && status.armor > 0 {
+            push_text_centered(verts, &format!("ARMOR {}", status.armor), cx, top + 62.0, 1.0, STATUS_LABEL);
         }
+        x += w;
     }
+}
 
-    let px = ox + player.x * scale;
-    let py = oy + player.y * scale;
-    let ps = scale * 0.4;
-    push_quad(verts, px - ps, py - ps, px + ps, py + ps, [1.0, 0.0, 0.0, 1.0]);
+/// The status bar face: bloodier as health drops, glancing around while idle.
+fn build_face(verts: &mut Vec<Vertex>, x: f32, y: f32, w: f32, health: i32, tick: u32) {
+    let fw = 44.0;
+    let fh = 56.0;
+    let fx = x + (w - fw) / 2.0;
+    let fy = y + (STATUS_BAR_H as f32 - fh) / 2.0;
+
+    let dead = health <= 0;
+    let skin = if dead { [0.55, 0.5, 0.45, 1.0] } else { [0.87, 0.66, 0.5, 1.0] };
+    push_rect_px(verts, fx, fy, fx + fw, fy + fh, skin);
+    push_rect_px(verts, fx, fy, fx + fw, fy + 10.0, [0.55, 0.35, 0.15, 1.0]);
+
+    let glance = if dead { 0.0 } else { ((tick / 90) % 3) as f32 - 1.0 };
+    let eye_y = fy + 20.0;
+    let eye_color = if dead { [0.5, 0.0, 0.0, 1.0] } else { [0.1, 0.2, 0.6, 1.0] };
+    for ex in [fx + 10.0, fx + fw - 16.0] {
+        push_rect_px(verts, ex, eye_y, ex + 6.0, eye_y + 6.0, [1.0, 1.0, 1.0, 1.0]);
+        let px = ex + 2.0 + glance * 2.0;
+        push_rect_px(verts, px, eye_y + 1.0, px + 2.0, eye_y + 5.0, eye_color);
+    }
+    push_rect_px(verts, fx + 14.0, fy + 42.0, fx + fw - 14.0, fy + 45.0, [0.5, 0.2, 0.2, 1.0]);
 
-    let dir_len = scale * 1.5;
-    let ex = px + player.angle.cos() * dir_len;
-    let ey = py + player.angle.sin() * dir_len;
-    let lw = scale * 0.12;
-    push_quad(verts, px - lw, py - lw, ex + lw, ey + lw, [1.0, 1.0, 0.0, 1.0]);
+    // One blood streak per 20 health lost.
+    let wounds = ((100 - health.clamp(0, 100)) / 20) as usize;
+    let streaks = [(4.0, 12.0), (fw - 10.0, 30.0), (18.0, 48.0), (8.0, 34.0), (fw - 14.0, 12.0)];
+    for &(sx, sy) in streaks.iter().take(wounds) {
+        push_rect_px(verts, fx + sx, fy + sy, fx + sx + 6.0, fy + sy + 8.0, [0.7, 0.0, 0.0, 1.0]);
+    }
 }
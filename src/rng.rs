@@ -0,0 +1,40 @@
+/// Seed used for a new game.
+pub const DEFAULT_SEED: u64 = 0x2545_F491_4F6C_DD1D;
+
+/// Xorshift64* generator. Its whole state is one word, so saves and demos can store and restore it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Rng {
+    pub state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // Xorshift gets stuck on zero.
+        Self { state: if seed == 0 { DEFAULT_SEED } else { seed } }
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let mut x = self.state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.state = x;
+        (x.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 32) as u32
+    }
+
+    /// Uniform in `0..n`; `n` must be non-zero.
+    pub fn below(&mut self, n: u32) -> u32 {
+        self.next_u32() % n
+    }
+
+    /// Wolf3D-style accuracy: true if a shot from `dist` tiles away lands. Every tile of distance
+    /// takes 16 off a 256 roll, so point-blank shots always land and shots from 16 tiles never do.
+    pub fn hits(&mut self, dist: f32) -> bool {
+        (self.below(256) as f32) >= dist * 16.0
+    }
+
+    /// Rolls the damage of a hit: uniform from half to one and a half times `base`.
+    pub fn damage(&mut self, base: i32) -> i32 {
+        base / 2 + self.below(base.max(0) as u32 + 1) as i32
+    }
+}
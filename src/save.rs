@@ -0,0 +1,367 @@
+use crate::actor::{ActorState, Dir, Enemy};
+use crate::difficulty::Difficulty;
+use crate::door::DoorAction;
+use crate::entity::EntityId;
+use crate::episode::Progress;
+use crate::items::{Item, ItemKind};
+use crate::map::Cell;
+use crate::player::{LifeState, Player, Weapon};
+use crate::projectile::{Owner, Projectile, ProjectileKind};
+use crate::pushwall::PushWallState;
+use crate::rng::Rng;
+use crate::storage;
+use crate::world::World;
+
+/// Bumped whenever the save layout changes; older saves are rejected rather than misread.
+pub const SAVE_VERSION: u32 = 8;
+const SAVE_MAGIC: &str = "WOLF3D-SAVE";
+/// Slot written by quicksave and read by quickload.
+pub const QUICK_SLOT: &str = "quick";
+/// Directory save files are kept in.
+pub const SAVE_DIR: &str = "saves";
+
+/// Snapshot of everything that changes while a level is played. The map and its entities are
+/// reloaded from its files on restore and the dynamic state is laid back over them.
+#[derive(Clone, Debug)]
+pub struct SaveGame {
+    pub map: String,
+    pub difficulty: Difficulty,
+    pub progress: Progress,
+    pub level_ticks: u32,
+    pub rng: u64,
+    pub player: Player,
+    /// Every enemy, in slot order. Enemies are never despawned, so this is also map order.
+    pub enemies: Vec<Enemy>,
+    /// `(open, action)` per door, in map order.
+    pub doors: Vec<(f32, DoorAction)>,
+    /// `(dir, offset, state)` per push wall, in map order.
+    pub pushwalls: Vec<((i32, i32), f32, PushWallState)>,
+    /// Picked flag per item placed in the map, in map order.
+    pub items: Vec<bool>,
+    /// Items dropped by enemies so far.
+    pub drops: Vec<Item>,
+    /// Projectiles in flight.
+    pub projectiles: Vec<Projectile>,
+}
+
+impl SaveGame {
+    pub fn capture(progress: Progress, world: &World) -> Self {
+        let map = &world.map;
+        Self {
+            map: world.name.clone(),
+            difficulty: world.difficulty,
+            progress,
+            level_ticks: world.level_ticks,
+            rng: world.rng.state,
+            player: world.player.clone(),
+            enemies: world.enemies.values().cloned().collect(),
+            doors: world.doors.values().map(|d| (d.open, d.action)).collect(),
+            pushwalls: map.pushwalls.iter().map(|p| (p.dir, p.offset, p.state)).collect(),
+            items: world.items.values().filter(|i| !i.dropped).map(|i| i.picked).collect(),
+            drops: world.items.values().filter(|i| i.dropped).cloned().collect(),
+            projectiles: world.projectiles.values().copied().collect(),
+        }
+    }
+
+    /// Reloads the saved map and applies the snapshot to it.
+    pub fn restore(&self) -> Result<World, String> {
+        let mut world = World::load(&self.map, self.difficulty)?;
+        let mismatch = || format!("save does not match map {}", self.map);
+        if world.enemies.len() != self.enemies.len()
+            || world.doors.len() != self.doors.len()
+            || world.map.pushwalls.len() != self.pushwalls.len()
+            || world.items.len() != self.items.len()
+        {
+            return Err(mismatch());
+        }
+
+        if world.enemies.values().zip(&self.enemies).any(|(a, b)| a.kind != b.kind) {
+            return Err(mismatch());
+        }
+        for (enemy, saved) in world.enemies.values_mut().zip(&self.enemies) {
+            *enemy = Enemy { state: enemy.state, ..saved.clone() };
+        }
+        // Projectile owners name enemy ids, which only line up if the enemy slots do.
+        for p in &self.projectiles {
+            if let Owner::Enemy(id) = p.owner {
+                if !world.enemies.contains(id) {
+                    return Err(mismatch());
+                }
+            }
+        }
+        for (door, &(open, action)) in world.doors.values_mut().zip(&self.doors) {
+            door.open = open;
+            door.action = action;
+        }
+        let map = &mut world.map;
+        for (item, &picked) in world.items.values_mut().zip(&self.items) {
+            item.picked = picked;
+        }
+        for drop in &self.drops {
+            world.items.spawn(drop.clone());
+        }
+        world.projectiles = self.projectiles.iter().copied().collect();
+        for (pw, &(dir, offset, state)) in map.pushwalls.iter_mut().zip(&self.pushwalls) {
+            pw.dir = dir;
+            pw.offset = offset;
+            pw.state = state;
+            // Redo the wall grid edits `push_wall` and `update_pushwalls` made.
+            if state != PushWallState::Idle {
+                map.walls[pw.y as usize][pw.x as usize] = Cell::Empty;
+            }
+            if state == PushWallState::Done {
+                let (tx, ty) = pw.tile();
+                map.walls[ty as usize][tx as usize] = Cell::Wall(pw.wall);
+            }
+        }
+        map.refresh_areas();
+
+        world.player = self.player.clone();
+        world.level_ticks = self.level_ticks;
+        world.rng = Rng::new(self.rng);
+        Ok(world)
+    }
+
+    /// Serialises to the line-based save format, one record per line.
+    pub fn to_text(&self) -> String {
+        let p = &self.player;
+        let mut out = format!("{SAVE_MAGIC} {SAVE_VERSION}\n");
+        out += &format!("map {}\n", self.map);
+        out += &format!("difficulty {}\n", self.difficulty.token());
+        let return_to = self.progress.return_to.map_or("-".to_string(), |i| i.to_string());
+        out += &format!("progress {} {}\n", self.progress.index, return_to);
+        out += &format!("ticks {}\n", self.level_ticks);
+        out += &format!("rng {}\n", self.rng);
+        let state = match p.state {
+            LifeState::Alive => "alive".to_string(),
+            LifeState::Dying { ticks } => format!("dying {ticks}"),
+            LifeState::GameOver => "gameover".to_string(),
+        };
+        out += &format!(
+            "player {} {} {} {} {} {} {} {} {} {} {} {} {} {} {}\n",
+            p.x, p.y, p.angle, p.health, p.armor, p.lives, p.score, p.ammo,
+            weapon_token(p.weapon), p.keys, p.pain_cooldown, p.fire_cooldown, p.noclip as u8, p.god as u8, state,
+        );
+        for e in &self.enemies {
+            out += &format!(
+                "enemy {} {} {} {} {} {} {} {}\n",
+                e.kind, e.x, e.y, e.alive as u8, e.alert as u8, e.facing.to_char(), e.health, e.cooldown,
+            );
+        }
+        for &(open, action) in &self.doors {
+            let action = match action {
+                DoorAction::Closed => "closed".to_string(),
+                DoorAction::Opening => "opening".to_string(),
+                DoorAction::Open { ticks } => format!("open {ticks}"),
+                DoorAction::Closing => "closing".to_string(),
+            };
+            out += &format!("door {open} {action}\n");
+        }
+        for &((dx, dy), offset, state) in &self.pushwalls {
+            let state = match state {
+                PushWallState::Idle => "idle",
+                PushWallState::Moving => "moving",
+                PushWallState::Done => "done",
+            };
+            out += &format!("pushwall {dx} {dy} {offset} {state}\n");
+        }
+        let items: String = self.items.iter().map(|&picked| if picked { '1' } else { '0' }).collect();
+        out += &format!("items {items}\n");
+        for item in &self.drops {
+            out += &format!("drop {} {} {} {}\n", item.kind.token(), item.x, item.y, item.picked as u8);
+        }
+        for p in &self.projectiles {
+            let owner = match p.owner {
+                Owner::Player => "player".to_string(),
+                Owner::Enemy(id) => format!("{}:{}", id.index(), id.generation()),
+            };
+            out += &format!(
+                "projectile {} {} {} {} {} {} {} {}\n",
+                p.kind.name(), p.x, p.y, p.dx, p.dy, p.damage, owner, p.travelled,
+            );
+        }
+        out
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines();
+        let header = lines.next().unwrap_or_default();
+        let version = header
+            .strip_prefix(SAVE_MAGIC)
+            .and_then(|v| v.trim().parse::<u32>().ok())
+            .ok_or("not a save file")?;
+        if version != SAVE_VERSION {
+            return Err(format!("unsupported save version {version} (expected {SAVE_VERSION})"));
+        }
+
+        let mut save = SaveGame {
+            map: String::new(),
+            difficulty: Difficulty::Medium,
+            progress: Progress::start(),
+            level_ticks: 0,
+            rng: 0,
+            player: Player::new(0.0, 0.0, 0.0),
+            enemies: Vec::new(),
+            doors: Vec::new(),
+            pushwalls: Vec::new(),
+            items: Vec::new(),
+            drops: Vec::new(),
+            projectiles: Vec::new(),
+        };
+        let mut have_player = false;
+
+        for (n, line) in lines.enumerate() {
+            let bad = || format!("bad save record on line {}: {line}", n + 2);
+            let mut t = line.split_whitespace();
+            let Some(key) = t.next() else { continue };
+            let rest: Vec<&str> = t.collect();
+            match (key, rest.as_slice()) {
+                ("map", [name]) => save.map = name.to_string(),
+                ("difficulty", [d]) => save.difficulty = Difficulty::from_token(d).ok_or_else(bad)?,
+                ("progress", [index, return_to]) => {
+                    save.progress.index = index.parse().map_err(|_| bad())?;
+                    save.progress.return_to = match *return_to {
+                        "-" => None,
+                        i => Some(i.parse().map_err(|_| bad())?),
+                    };
+                }
+                ("ticks", [ticks]) => save.level_ticks = ticks.parse().map_err(|_| bad())?,
+                ("rng", [rng]) => save.rng = rng.parse().map_err(|_| bad())?,
+                ("player", [x, y, angle, health, armor, lives, score, ammo, weapon, keys, pain, fire, noclip, god, state @ ..]) => {
+                    let p = &mut save.player;
+                    p.x = x.parse().map_err(|_| bad())?;
+                    p.y = y.parse().map_err(|_| bad())?;
+                    p.angle = angle.parse().map_err(|_| bad())?;
+                    p.health = health.parse().map_err(|_| bad())?;
+                    p.armor = armor.parse().map_err(|_| bad())?;
+                    p.lives = lives.parse().map_err(|_| bad())?;
+                    p.score = score.parse().map_err(|_| bad())?;
+                    p.ammo = ammo.parse().map_err(|_| bad())?;
+                    p.weapon = parse_weapon(weapon).ok_or_else(bad)?;
+                    p.keys = keys.parse().map_err(|_| bad())?;
+                    p.pain_cooldown = pain.parse().map_err(|_| bad())?;
+                    p.fire_cooldown = fire.parse().map_err(|_| bad())?;
+                    p.noclip = *noclip == "1";
+                    p.god = *god == "1";
+                    p.state = match state {
+                        ["alive"] => LifeState::Alive,
+                        ["dying", ticks] => LifeState::Dying { ticks: ticks.parse().map_err(|_| bad())? },
+                        ["gameover"] => LifeState::GameOver,
+                        _ => return Err(bad()),
+                    };
+                    have_player = true;
+                }
+                ("enemy", [kind, x, y, alive, alert, facing, health, cooldown]) => {
+                    let mut facing = facing.chars();
+                    let facing = match (facing.next().and_then(Dir::from_char), facing.next()) {
+                        (Some(dir), None) => dir,
+                        _ => return Err(bad()),
+                    };
+                    save.enemies.push(Enemy {
+                        kind: kind.parse().map_err(|_| bad())?,
+                        x: x.parse().map_err(|_| bad())?,
+                        y: y.parse().map_err(|_| bad())?,
+                        alive: *alive == "1",
+                        alert: *alert == "1",
+                        facing,
+                        // Not saved: it never changes, so `restore` keeps the map's.
+                        state: ActorState::Standing,
+                        health: health.parse().map_err(|_| bad())?,
+                        cooldown: cooldown.parse().map_err(|_| bad())?,
+                    });
+                }
+                ("door", [open, action @ ..]) => {
+                    let action = match action {
+                        ["closed"] => DoorAction::Closed,
+                        ["opening"] => DoorAction::Opening,
+                        ["open", ticks] => DoorAction::Open { ticks: ticks.parse().map_err(|_| bad())? },
+                        ["closing"] => DoorAction::Closing,
+                        _ => return Err(bad()),
+                    };
+                    save.doors.push((open.parse().map_err(|_| bad())?, action));
+                }
+                ("pushwall", [dx, dy, offset, state]) => {
+                    let state = match *state {
+                        "idle" => PushWallState::Idle,
+                        "moving" => PushWallState::Moving,
+                        "done" => PushWallState::Done,
+                        _ => return Err(bad()),
+                    };
+                    let dir = (dx.parse().map_err(|_| bad())?, dy.parse().map_err(|_| bad())?);
+                    save.pushwalls.push((dir, offset.parse().map_err(|_| bad())?, state));
+                }
+                ("items", [bits]) => save.items = bits.chars().map(|c| c == '1').collect(),
+                ("items", []) => save.items.clear(),
+                ("drop", [kind, x, y, picked]) => save.drops.push(Item {
+                    kind: ItemKind::from_token(kind).ok_or_else(bad)?,
+                    x: x.parse().map_err(|_| bad())?,
+                    y: y.parse().map_err(|_| bad())?,
+                    picked: *picked == "1",
+                    dropped: true,
+                }),
+                ("projectile", [kind, x, y, dx, dy, damage, owner, travelled]) => {
+                    save.projectiles.push(Projectile {
+                        kind: ProjectileKind::from_name(kind).ok_or_else(bad)?,
+                        x: x.parse().map_err(|_| bad())?,
+                        y: y.parse().map_err(|_| bad())?,
+                        dx: dx.parse().map_err(|_| bad())?,
+                        dy: dy.parse().map_err(|_| bad())?,
+                        damage: damage.parse().map_err(|_| bad())?,
+                        owner: match *owner {
+                            "player" => Owner::Player,
+                            id => {
+                                let (index, generation) = id.split_once(':').ok_or_else(bad)?;
+                                let index = index.parse().map_err(|_| bad())?;
+                                Owner::Enemy(EntityId::from_parts(index, generation.parse().map_err(|_| bad())?))
+                            }
+                        },
+                        travelled: travelled.parse().map_err(|_| bad())?,
+                    });
+                }
+                _ => return Err(bad()),
+            }
+        }
+
+        if save.map.is_empty() || !have_player {
+            return Err("save is missing its map or player record".to_string());
+        }
+        Ok(save)
+    }
+}
+
+fn weapon_token(weapon: Weapon) -> &'static str {
+    match weapon {
+        Weapon::Knife => "knife",
+        Weapon::Pistol => "pistol",
+        Weapon::MachineGun => "machinegun",
+        Weapon::Chaingun => "chaingun",
+    }
+}
+
+fn parse_weapon(token: &str) -> Option<Weapon> {
+    Some(match token {
+        "knife" => Weapon::Knife,
+        "pistol" => Weapon::Pistol,
+        "machinegun" => Weapon::MachineGun,
+        "chaingun" => Weapon::Chaingun,
+        _ => return None,
+    })
+}
+
+fn slot_path(slot: &str) -> Result<String, String> {
+    storage::slot_path(SAVE_DIR, slot, "sav")
+}
+
+/// Writes `save` to the named slot: `saves/<slot>.sav` on native, localStorage on the web.
+pub fn write_slot(slot: &str, save: &SaveGame) -> Result<(), String> {
+    storage::write(&slot_path(slot)?, &save.to_text())
+}
+
+pub fn read_slot(slot: &str) -> Result<SaveGame, String> {
+    let text = storage::read(&slot_path(slot)?).map_err(|e| format!("no save in slot {slot}: {e}"))?;
+    SaveGame::parse(&text)
+}
+
+pub fn slot_exists(slot: &str) -> bool {
+    slot_path(slot).is_ok_and(|path| storage::exists(&path))
+}
@@ -0,0 +1,205 @@
+use crate::bestiary::{EnemyDef, SpriteSet};
+use crate::items::ItemKind;
+use crate::projectile::{Projectile, ProjectileKind};
+
+/// One flat-coloured rectangle of a sprite, in sprite space: x runs 0..1 left to right,
+/// y runs 0..1 top to bottom.
+#[derive(Clone, Copy, Debug)]
+pub struct SpriteLayer {
+    pub x0: f32,
+    pub y0: f32,
+    pub x1: f32,
+    pub y1: f32,
+    pub color: [f32; 4],
+    /// Drawn in the sprite's tint instead of `color`.
+    pub tinted: bool,
+}
+
+const fn layer(x0: f32, y0: f32, x1: f32, y1: f32, color: [f32; 4]) -> SpriteLayer {
+    SpriteLayer { x0, y0, x1, y1, color, tinted: false }
+}
+
+const fn tinted(x0: f32, y0: f32, x1: f32, y1: f32) -> SpriteLayer {
+    SpriteLayer { x0, y0, x1, y1, color: WHITE, tinted: true }
+}
+
+/// A billboard standing on the floor at `(x, y)`, `size` wall-heights tall and wide.
+#[derive(Clone, Copy, Debug)]
+pub struct Sprite {
+    pub x: f32,
+    pub y: f32,
+    pub size: f32,
+    pub layers: &'static [SpriteLayer],
+    /// Colour of the `tinted` layers.
+    pub tint: [f32; 4],
+}
+
+impl SpriteLayer {
+    pub fn color(&self, sprite: &Sprite) -> [f32; 4] {
+        if self.tinted { sprite.tint } else { self.color }
+    }
+}
+
+const WHITE: [f32; 4] = [0.95, 0.95, 0.95, 1.0];
+const RED: [f32; 4] = [0.8, 0.05, 0.05, 1.0];
+const GOLD: [f32; 4] = [0.95, 0.75, 0.1, 1.0];
+const SILVER: [f32; 4] = [0.75, 0.78, 0.82, 1.0];
+const BROWN: [f32; 4] = [0.45, 0.28, 0.12, 1.0];
+const GUNMETAL: [f32; 4] = [0.25, 0.25, 0.28, 1.0];
+const GREEN: [f32; 4] = [0.2, 0.55, 0.25, 1.0];
+
+const FIRST_AID: &[SpriteLayer] = &[
+    layer(0.1, 0.4, 0.9, 1.0, WHITE),
+    layer(0.42, 0.5, 0.58, 0.9, RED),
+    layer(0.25, 0.62, 0.75, 0.78, RED),
+];
+const FOOD: &[SpriteLayer] = &[
+    layer(0.05, 0.8, 0.95, 1.0, WHITE),
+    layer(0.2, 0.6, 0.8, 0.85, BROWN),
+    layer(0.55, 0.5, 0.7, 0.65, GREEN),
+];
+const DOG_FOOD: &[SpriteLayer] = &[
+    layer(0.2, 0.85, 0.8, 1.0, SILVER),
+    layer(0.3, 0.72, 0.7, 0.88, BROWN),
+];
+const CLIP: &[SpriteLayer] = &[
+    layer(0.35, 0.55, 0.65, 1.0, GUNMETAL),
+    layer(0.4, 0.5, 0.6, 0.58, GOLD),
+];
+const CROSS: &[SpriteLayer] = &[
+    layer(0.44, 0.45, 0.56, 1.0, GOLD),
+    layer(0.3, 0.58, 0.7, 0.68, GOLD),
+];
+const CHALICE: &[SpriteLayer] = &[
+    layer(0.3, 0.5, 0.7, 0.7, GOLD),
+    layer(0.45, 0.7, 0.55, 0.9, GOLD),
+    layer(0.35, 0.9, 0.65, 1.0, GOLD),
+];
+const CHEST: &[SpriteLayer] = &[
+    layer(0.15, 0.6, 0.85, 1.0, BROWN),
+    layer(0.15, 0.7, 0.85, 0.74, GOLD),
+    layer(0.45, 0.7, 0.55, 0.82, GOLD),
+];
+const CROWN: &[SpriteLayer] = &[
+    layer(0.25, 0.75, 0.75, 0.95, GOLD),
+    layer(0.25, 0.6, 0.33, 0.75, GOLD),
+    layer(0.46, 0.58, 0.54, 0.75, GOLD),
+    layer(0.67, 0.6, 0.75, 0.75, GOLD),
+    layer(0.45, 0.8, 0.55, 0.88, RED),
+];
+const GOLD_KEY: &[SpriteLayer] = &[
+    layer(0.4, 0.55, 0.6, 0.7, GOLD),
+    layer(0.47, 0.7, 0.53, 0.95, GOLD),
+    layer(0.53, 0.85, 0.62, 0.9, GOLD),
+];
+const SILVER_KEY: &[SpriteLayer] = &[
+    layer(0.4, 0.55, 0.6, 0.7, SILVER),
+    layer(0.47, 0.7, 0.53, 0.95, SILVER),
+    layer(0.53, 0.85, 0.62, 0.9, SILVER),
+];
+const MACHINE_GUN: &[SpriteLayer] = &[
+    layer(0.1, 0.75, 0.9, 0.85, GUNMETAL),
+    layer(0.55, 0.85, 0.65, 1.0, GUNMETAL),
+    layer(0.1, 0.78, 0.3, 0.92, BROWN),
+];
+const CHAINGUN: &[SpriteLayer] = &[
+    layer(0.05, 0.72, 0.95, 0.88, GUNMETAL),
+    layer(0.05, 0.76, 0.25, 0.84, SILVER),
+    layer(0.5, 0.88, 0.62, 1.0, GUNMETAL),
+];
+const EXTRA_LIFE: &[SpriteLayer] = &[
+    layer(0.25, 0.45, 0.75, 0.95, [0.2, 0.45, 0.85, 1.0]),
+    layer(0.35, 0.55, 0.65, 0.85, [0.87, 0.66, 0.5, 1.0]),
+    layer(0.4, 0.62, 0.46, 0.68, WHITE),
+    layer(0.54, 0.62, 0.6, 0.68, WHITE),
+];
+
+pub fn item_sprite(kind: ItemKind, x: f32, y: f32) -> Sprite {
+    let layers = match kind {
+        ItemKind::FirstAid => FIRST_AID,
+        ItemKind::Food => FOOD,
+        ItemKind::DogFood => DOG_FOOD,
+        ItemKind::Clip => CLIP,
+        ItemKind::Cross => CROSS,
+        ItemKind::Chalice => CHALICE,
+        ItemKind::Chest => CHEST,
+        ItemKind::Crown => CROWN,
+        ItemKind::GoldKey => GOLD_KEY,
+        ItemKind::SilverKey => SILVER_KEY,
+        ItemKind::MachineGun => MACHINE_GUN,
+        ItemKind::Chaingun => CHAINGUN,
+        ItemKind::ExtraLife => EXTRA_LIFE,
+    };
+    Sprite { x, y, size: 0.5, layers, tint: WHITE }
+}
+
+const SKIN: [f32; 4] = [0.87, 0.66, 0.5, 1.0];
+const PALE: [f32; 4] = [0.7, 0.8, 0.65, 1.0];
+const BLACK: [f32; 4] = [0.05, 0.05, 0.05, 1.0];
+
+const SOLDIER: &[SpriteLayer] = &[
+    tinted(0.32, 0.6, 0.46, 1.0),
+    tinted(0.54, 0.6, 0.68, 1.0),
+    tinted(0.25, 0.24, 0.75, 0.64),
+    layer(0.38, 0.06, 0.62, 0.26, SKIN),
+    tinted(0.36, 0.02, 0.64, 0.1),
+    layer(0.6, 0.36, 0.9, 0.44, GUNMETAL),
+];
+const DOG: &[SpriteLayer] = &[
+    tinted(0.1, 0.58, 0.72, 0.8),
+    tinted(0.62, 0.45, 0.92, 0.66),
+    tinted(0.14, 0.8, 0.22, 1.0),
+    tinted(0.58, 0.8, 0.66, 1.0),
+    layer(0.8, 0.5, 0.85, 0.55, BLACK),
+];
+const MUTANT: &[SpriteLayer] = &[
+    tinted(0.32, 0.6, 0.46, 1.0),
+    tinted(0.54, 0.6, 0.68, 1.0),
+    tinted(0.25, 0.24, 0.75, 0.64),
+    layer(0.38, 0.06, 0.62, 0.26, PALE),
+    layer(0.44, 0.36, 0.56, 0.44, GUNMETAL),
+    layer(0.1, 0.36, 0.3, 0.42, GUNMETAL),
+];
+const BOSS: &[SpriteLayer] = &[
+    tinted(0.22, 0.62, 0.44, 1.0),
+    tinted(0.56, 0.62, 0.78, 1.0),
+    tinted(0.12, 0.2, 0.88, 0.66),
+    layer(0.36, 0.0, 0.64, 0.22, SKIN),
+    layer(0.0, 0.34, 0.22, 0.46, GUNMETAL),
+    layer(0.78, 0.34, 1.0, 0.46, GUNMETAL),
+];
+
+pub fn enemy_sprite(def: &EnemyDef, x: f32, y: f32) -> Sprite {
+    let (layers, size) = match def.sprite {
+        SpriteSet::Soldier => (SOLDIER, 0.85),
+        SpriteSet::Dog => (DOG, 0.5),
+        SpriteSet::Mutant => (MUTANT, 0.85),
+        SpriteSet::Boss => (BOSS, 1.0),
+    };
+    Sprite { x, y, size, layers, tint: def.color }
+}
+
+// Projectiles fly at gun height, so their layers sit mid-way up a full wall-height billboard.
+const ROCKET: &[SpriteLayer] = &[
+    layer(0.44, 0.46, 0.56, 0.54, GUNMETAL),
+    layer(0.46, 0.48, 0.54, 0.52, RED),
+    layer(0.47, 0.54, 0.53, 0.58, [1.0, 0.7, 0.2, 1.0]),
+];
+const FIREBALL: &[SpriteLayer] = &[
+    layer(0.42, 0.43, 0.58, 0.57, [1.0, 0.45, 0.05, 1.0]),
+    layer(0.46, 0.47, 0.54, 0.53, [1.0, 0.9, 0.4, 1.0]),
+];
+const SYRINGE: &[SpriteLayer] = &[
+    layer(0.47, 0.46, 0.53, 0.54, WHITE),
+    layer(0.48, 0.48, 0.52, 0.52, GREEN),
+    layer(0.495, 0.54, 0.505, 0.58, SILVER),
+];
+
+pub fn projectile_sprite(p: &Projectile) -> Sprite {
+    let layers = match p.kind {
+        ProjectileKind::Rocket => ROCKET,
+        ProjectileKind::Fireball => FIREBALL,
+        ProjectileKind::Syringe => SYRINGE,
+    };
+    Sprite { x: p.x, y: p.y, size: 1.0, layers, tint: WHITE }
+}
@@ -0,0 +1,68 @@
+/// Persistent text files: paths under the working directory on native builds,
+/// `localStorage` entries keyed by the same path in the browser.
+pub fn write(path: &str, text: &str) -> Result<(), String> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let path = std::path::Path::new(path);
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(path, text).map_err(|e| e.to_string())
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        local_storage()?
+            .set_item(&storage_key(path), text)
+            .map_err(|_| "localStorage write failed".to_string())
+    }
+}
+
+pub fn read(path: &str) -> Result<String, String> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        local_storage()?
+            .get_item(&storage_key(path))
+            .ok()
+            .flatten()
+            .ok_or_else(|| format!("{path}: not found"))
+    }
+}
+
+/// Path of the named slot in `dir`, such as `saves/quick.sav`. Slot names become file names
+/// and storage keys, so they are kept to letters, digits, `-` and `_`.
+pub fn slot_path(dir: &str, slot: &str, ext: &str) -> Result<String, String> {
+    if slot.is_empty() || !slot.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(format!("invalid slot name {slot:?}"));
+    }
+    Ok(format!("{dir}/{slot}.{ext}"))
+}
+
+pub fn exists(path: &str) -> bool {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::path::Path::new(path).is_file()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        local_storage().is_ok_and(|s| s.get_item(&storage_key(path)).ok().flatten().is_some())
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn storage_key(path: &str) -> String {
+    format!("wolf3d_rs/{path}")
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Result<web_sys::Storage, String> {
+    web_sys::window()
+        .and_then(|w| w.local_storage().ok().flatten())
+        .ok_or_else(|| "localStorage is unavailable".to_string())
+}
@@ -0,0 +1,325 @@
+use crate::actor::{parse_enemies, Enemy, EnemyHit};
+use crate::ai;
+use crate::audio::{self, SoundId};
+use crate::bestiary::{Attack, Bestiary};
+use crate::difficulty::Difficulty;
+use crate::door::{Door, DoorAction};
+use crate::entity::{Entities, EntityId};
+use crate::items::{self, parse_items, Item};
+use crate::light::{Lighting, PointLight};
+use crate::map::{load_layer, ExitKind, Map};
+use crate::path::FlowCache;
+use crate::player::{Player, UseOutcome, Weapon, MOVE_SPEED, ROT_SPEED};
+use crate::projectile::{self, Owner, Projectile, ProjectileKind};
+use crate::rng::{self, Rng};
+
+/// What the player does in one tick, already mapped from whatever keys are bound.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct TickInput {
+    pub forward: bool,
+    pub back: bool,
+    pub turn_left: bool,
+    pub turn_right: bool,
+    /// Held to fire; repeats at the weapon's rate.
+    pub fire: bool,
+    /// Use was pressed since the last tick.
+    pub use_pressed: bool,
+}
+
+/// Something that happened during a tick, for the front end to present.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Event {
+    /// A sound heard right at the player.
+    Sound(SoundId),
+    /// A sound made at a point in the world.
+    SoundAt(SoundId, f32, f32),
+    /// A line for the HUD.
+    Message(String),
+    /// The player flipped an exit switch.
+    Exit(ExitKind),
+}
+
+/// One step of a tick. Systems run in schedule order and report what happened as events.
+pub type System = fn(&mut World, &TickInput, &mut Vec<Event>);
+
+/// The systems a new world runs each tick, in order.
+pub const SCHEDULE: &[(&str, System)] = &[
+    ("player", player_system),
+    ("weapons", weapon_system),
+    ("pickups", pickup_system),
+    ("pushwalls", pushwall_system),
+    ("patrols", patrol_system),
+    ("ai", ai_system),
+    ("projectiles", projectile_system),
+    ("doors", door_system),
+    ("senses", sense_system),
+    ("lights", light_system),
+];
+
+/// A level being played: the static `Map` geometry plus every entity that moves, spawns or dies
+/// on it. The map keeps which door fills each tile; the doors themselves live here. Push walls
+/// rewrite the wall grid as they slide, so they stay in the map.
+pub struct World {
+    /// Name of the loaded map.
+    pub name: String,
+    pub map: Map,
+    pub doors: Entities<Door>,
+    /// Enemy kinds; every `Enemy::kind` indexes into it.
+    pub bestiary: Bestiary,
+    pub player: Player,
+    pub enemies: Entities<Enemy>,
+    pub items: Entities<Item>,
+    pub projectiles: Entities<Projectile>,
+    pub lighting: Lighting,
+    /// Routes for enemies chasing the player.
+    pub flow: FlowCache,
+    pub difficulty: Difficulty,
+    pub rng: Rng,
+    /// Ticks spent on this level, for the intermission time.
+    pub level_ticks: u32,
+    /// Tiles walked per tick.
+    pub move_speed: f32,
+    /// Radians turned per tick.
+    pub rot_speed: f32,
+    /// Systems `tick` runs, in order; starts as `SCHEDULE`.
+    pub schedule: Vec<(&'static str, System)>,
+    /// Door slides as the running tick began, so the door system can sound the changes.
+    doors_before: Vec<DoorAction>,
+    alive_before: bool,
+}
+
+impl World {
+    /// Loads map `name` and spawns its enemies and items. Enemies tagged for a higher
+    /// `difficulty` are left out. The player starts fresh at the map's spawn point.
+    pub fn load(name: &str, difficulty: Difficulty) -> Result<Self, String> {
+        let mut map = Map::load(name)?;
+        let doors = map.spawn_doors();
+        let bestiary = Bestiary::load()?;
+        let enemies = parse_enemies(&load_layer(name, "enemies"), difficulty, &bestiary).into_iter().collect();
+        let items = parse_items(&load_layer(name, "items")).into_iter().collect();
+        let (x, y, angle) = map.player_start;
+        let lighting = Lighting::new(&map);
+        Ok(Self {
+            name: name.to_string(),
+            map,
+            doors,
+            bestiary,
+            player: Player::new(x, y, angle),
+            enemies,
+            items,
+            projectiles: Entities::default(),
+            lighting,
+            flow: FlowCache::default(),
+            difficulty,
+            rng: Rng::new(rng::DEFAULT_SEED),
+            level_ticks: 0,
+            move_speed: MOVE_SPEED,
+            rot_speed: ROT_SPEED,
+            schedule: SCHEDULE.to_vec(),
+            doors_before: Vec::new(),
+            alive_before: true,
+        })
+    }
+
+    /// Moves on to map `name`, keeping the player's stats, the difficulty, the RNG and settings.
+    pub fn enter_map(&mut self, name: &str) -> Result<(), String> {
+        let next = Self::load(name, self.difficulty)?;
+        let player = std::mem::replace(&mut self.player, next.player.clone());
+        *self = Self { player, rng: self.rng, move_speed: self.move_speed, rot_speed: self.rot_speed, ..next };
+        self.player.enter_level(&self.map);
+        Ok(())
+    }
+
+    /// Runs every scheduled system once and returns what happened.
+    pub fn tick(&mut self, input: &TickInput) -> Vec<Event> {
+        let mut events = Vec::new();
+        self.doors_before = self.doors.values().map(|d| d.action).collect();
+        self.alive_before = self.player.is_alive();
+        for i in 0..self.schedule.len() {
+            let (_, system) = self.schedule[i];
+            system(self, input, &mut events);
+        }
+        self.level_ticks += 1;
+        events
+    }
+
+    pub fn kills_total(&self) -> usize {
+        self.enemies.len()
+    }
+
+    pub fn kills(&self) -> usize {
+        self.enemies.values().filter(|e| !e.alive).count()
+    }
+
+    pub fn treasure_total(&self) -> usize {
+        self.items.values().filter(|i| i.kind.is_treasure()).count()
+    }
+
+    pub fn treasure_found(&self) -> usize {
+        self.items.values().filter(|i| i.kind.is_treasure() && i.picked).count()
+    }
+
+    /// Kills every living enemy as if the player had shot it: score, drops and death sounds.
+    pub fn kill_all(&mut self) -> Vec<Event> {
+        let mut events = Vec::new();
+        for id in self.enemies.ids() {
+            if self.enemies[id].alive {
+                let hit = self.damage_enemy(id, self.enemies[id].health);
+                self.report_hit(id, hit, true, &mut events);
+            }
+        }
+        events
+    }
+
+    /// Uses whatever the player faces: doors, push walls and exit switches.
+    fn use_front(&mut self, events: &mut Vec<Event>) {
+        match self.player.use_front(&mut self.map, &mut self.doors) {
+            UseOutcome::Locked(key) => {
+                events.push(Event::Message(format!("YOU NEED THE {} KEY", key.name())));
+                events.push(Event::Sound(SoundId::Denied));
+            }
+            UseOutcome::Secret => {
+                events.push(Event::Message("YOU FOUND A SECRET!".to_string()));
+                let (x, y) = self.player.facing_tile();
+                events.push(Event::SoundAt(SoundId::PushWall, x as f32 + 0.5, y as f32 + 0.5));
+            }
+            UseOutcome::Exit(kind) => events.push(Event::Exit(kind)),
+            // Door sounds come from the door system, which sees the slide change.
+            UseOutcome::Door | UseOutcome::Nothing => {}
+        }
+    }
+
+    /// Sounds for enemy `id` taking a hit, scoring the kill if the player dealt it.
+    fn report_hit(&mut self, id: EntityId, hit: EnemyHit, by_player: bool, events: &mut Vec<Event>) {
+        let enemy = &self.enemies[id];
+        let def = self.bestiary.get(enemy.kind);
+        match hit {
+            EnemyHit::Killed => {
+                if by_player {
+                    self.player.score += def.score;
+                }
+                events.push(Event::SoundAt(def.death_sound, enemy.x, enemy.y));
+            }
+            // Bosses roar as they enter each new phase.
+            EnemyHit::NewPhase(_) => events.push(Event::SoundAt(def.alert_sound, enemy.x, enemy.y)),
+            EnemyHit::Hurt => {}
+        }
+    }
+
+    /// Each newly alerted enemy's alert sound, from where it stands.
+    fn alert_sounds(&self, enemies: &[EntityId], events: &mut Vec<Event>) {
+        for &id in enemies {
+            let enemy = &self.enemies[id];
+            events.push(Event::SoundAt(self.bestiary.get(enemy.kind).alert_sound, enemy.x, enemy.y));
+        }
+    }
+}
+
+fn player_system(world: &mut World, input: &TickInput, events: &mut Vec<Event>) {
+    world.player.update(input, &world.map, &world.doors, world.move_speed, world.rot_speed);
+    if input.use_pressed {
+        world.use_front(events);
+    }
+}
+
+fn weapon_system(world: &mut World, input: &TickInput, events: &mut Vec<Event>) {
+    if !input.fire {
+        return;
+    }
+    let Some(weapon) = world.player.fire() else { return };
+    let (px, py) = (world.player.x, world.player.y);
+    if weapon == Weapon::Knife {
+        events.push(Event::Sound(SoundId::Knife));
+    } else {
+        events.push(Event::Sound(SoundId::Gunfire));
+        world.lighting.spawn(PointLight::muzzle_flash(px, py));
+    }
+    if let Some(id) = world.player.aim_target(&world.map, &world.doors, &world.enemies, weapon.range()) {
+        // Bullets scatter with distance; the knife never misses.
+        let e = &world.enemies[id];
+        let dist = ((e.x - px).powi(2) + (e.y - py).powi(2)).sqrt();
+        if weapon == Weapon::Knife || world.rng.hits(dist) {
+            let damage = world.rng.damage(weapon.damage());
+            let hit = world.damage_enemy(id, damage);
+            world.report_hit(id, hit, true, events);
+        }
+    }
+    // Gunfire carries through every open door; the knife makes no noise.
+    if weapon != Weapon::Knife {
+        let heard = world.alert_enemies(px, py);
+        world.alert_sounds(&heard, events);
+    }
+}
+
+fn pickup_system(world: &mut World, _: &TickInput, events: &mut Vec<Event>) {
+    if world.player.is_alive() {
+        for _ in items::touch_items(&mut world.items, &mut world.player, world.difficulty) {
+            events.push(Event::Sound(SoundId::Pickup));
+        }
+    }
+}
+
+fn pushwall_system(world: &mut World, _: &TickInput, _: &mut Vec<Event>) {
+    world.map.update_pushwalls();
+}
+
+fn patrol_system(world: &mut World, _: &TickInput, _: &mut Vec<Event>) {
+    world.update_patrols();
+}
+
+fn ai_system(world: &mut World, _: &TickInput, events: &mut Vec<Event>) {
+    for id in ai::update(world) {
+        let enemy = &world.enemies[id];
+        let def = world.bestiary.get(enemy.kind);
+        let sound = match def.phase(enemy.health).attack {
+            Attack::Projectile(_) => SoundId::Launch,
+            Attack::Melee | Attack::Hitscan => def.attack_sound,
+        };
+        events.push(Event::SoundAt(sound, enemy.x, enemy.y));
+    }
+}
+
+fn projectile_system(world: &mut World, _: &TickInput, events: &mut Vec<Event>) {
+    for blast in projectile::update(world) {
+        if blast.kind.splash_radius() > 0.0 {
+            events.push(Event::SoundAt(SoundId::Explosion, blast.x, blast.y));
+            world.lighting.spawn(PointLight::explosion(blast.x, blast.y));
+        }
+        for &(id, hit) in &blast.enemies {
+            world.report_hit(id, hit, blast.owner == Owner::Player, events);
+        }
+    }
+}
+
+fn door_system(world: &mut World, _: &TickInput, events: &mut Vec<Event>) {
+    let player = (world.player.x as i32, world.player.y as i32);
+    let enemies = world.enemies.values().filter(|e| e.alive).map(Enemy::tile);
+    let occupied: Vec<(i32, i32)> = std::iter::once(player).chain(enemies).collect();
+    // Doors will not close on anyone standing in them.
+    for door in world.doors.values_mut() {
+        door.update(occupied.contains(&(door.x, door.y)));
+    }
+    for (sound, x, y) in audio::door_sounds(&world.doors_before, &world.doors) {
+        events.push(Event::SoundAt(sound, x, y));
+    }
+}
+
+fn sense_system(world: &mut World, _: &TickInput, events: &mut Vec<Event>) {
+    if world.alive_before && !world.player.is_alive() {
+        events.push(Event::Sound(SoundId::PlayerDeath));
+    }
+    if world.player.is_alive() {
+        let seen = world.sight_alerts(world.player.x, world.player.y);
+        world.alert_sounds(&seen, events);
+    }
+}
+
+fn light_system(world: &mut World, _: &TickInput, _: &mut Vec<Event>) {
+    world.lighting.update();
+    // Spawned after the update so each glow is lit for the frame drawn after this tick.
+    for p in world.projectiles.values() {
+        if p.kind != ProjectileKind::Syringe {
+            world.lighting.spawn(PointLight::projectile(p.x, p.y));
+        }
+    }
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use wolf3d_rs::actor::ActorState;
+use wolf3d_rs::entity::EntityId;
+use wolf3d_rs::world::World;
+
+use common::{map1, slide_doors, world1};
+
+/// The officer locked in map1's exit room, behind the gold door at (12, 13).
+fn officer(world: &World) -> EntityId {
+    let officer = world.bestiary.find("officer").unwrap();
+    world.enemies.iter().find(|(_, e)| e.kind == officer).map(|(id, _)| id).expect("map1 has an officer")
+}
+
+#[test]
+fn doors_split_floor_into_areas() {
+    let (map, doors) = map1();
+    let hall = map.areas.area_at(&map, 2.5, 2.5).unwrap();
+    let exit_room = map.areas.area_at(&map, 11.5, 11.5).unwrap();
+    assert_ne!(hall, exit_room);
+    // Both sides of the stone door at (7, 7) are part of the same hall.
+    assert_eq!(map.areas.area_at(&map, 7.5, 6.5), Some(hall));
+    assert_eq!(map.areas.area_at(&map, 7.5, 8.5), Some(hall));
+    // Standing in a doorway counts as being beside it; walls are in no area.
+    assert!(map.areas.area_at(&map, 12.5, 13.5).is_some());
+    assert_eq!(map.areas.area_at(&map, 0.5, 0.5), None);
+    assert!(!map.areas.reachable(&map, &doors, hall)[exit_room as usize]);
+}
+
+#[test]
+fn gunfire_alerts_exactly_the_connected_areas() {
+    let mut world = world1();
+    let officer = officer(&world);
+
+    let mut heard = world.alert_enemies(2.5, 2.5);
+    heard.sort_unstable();
+    // Everyone in the hall hears it, except the SS lying in ambush.
+    let expected: Vec<EntityId> = world
+        .enemies
+        .iter()
+        .filter(|&(id, e)| id != officer && e.state != ActorState::Ambush)
+        .map(|(id, _)| id)
+        .collect();
+    assert!(expected.len() + 2 == world.enemies.len());
+    assert_eq!(heard, expected);
+    assert!(!world.enemies[officer].alert);
+
+    // Once the gold door starts to open the exit room hears too, and only the officer is new.
+    world.map.door_at_mut(&mut world.doors, 12, 13).unwrap().activate();
+    slide_doors(&mut world.doors, 1);
+    assert_eq!(world.alert_enemies(2.5, 2.5), vec![officer]);
+    assert!(world.alert_enemies(2.5, 2.5).is_empty());
+}
+
+#[test]
+fn closed_doors_block_sound_again() {
+    let mut world = world1();
+    let officer = officer(&world);
+    let door = world.map.door_at_mut(&mut world.doors, 12, 13).unwrap();
+    door.activate();
+    door.activate();
+    slide_doors(&mut world.doors, 2);
+    assert!(world.map.door_open(&world.doors, 12, 13) == 0.0);
+    assert!(!world.alert_enemies(2.5, 2.5).contains(&officer));
+}
+
+#[test]
+fn dead_enemies_are_not_alerted() {
+    let mut world = world1();
+    let first = world.enemies.ids()[0];
+    world.enemies[first].alive = false;
+    assert!(!world.alert_enemies(2.5, 2.5).contains(&first));
+    assert!(!world.enemies[first].alert);
+}
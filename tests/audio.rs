@@ -0,0 +1,118 @@
+mod common;
+
+use std::f32::consts::FRAC_PI_2;
+
+use wolf3d_rs::audio::{door_sounds, Audio, AudioEvent, Mixer, Music, RecordingBackend, SoundId, Spatial};
+use wolf3d_rs::door::{Door, DOOR_OPEN_TICKS, DOOR_SPEED};
+use wolf3d_rs::entity::Entities;
+use wolf3d_rs::player::{Player, UseOutcome};
+use wolf3d_rs::world::{Event, TickInput};
+
+use common::{map1, slide_doors, world1};
+
+/// Runs `ticks` door updates on `doors`, playing whatever door sounds each tick produces.
+fn run_doors(doors: &mut Entities<Door>, audio: &mut Audio, ticks: u32) {
+    for _ in 0..ticks {
+        let before: Vec<_> = doors.values().map(|d| d.action).collect();
+        slide_doors(doors, 1);
+        for (sound, x, y) in door_sounds(&before, doors) {
+            audio.play_at(sound, x, y);
+        }
+        audio.advance();
+    }
+}
+
+#[test]
+fn door_open_and_close_sound_on_their_ticks() {
+    let recording = RecordingBackend::default();
+    let mut audio = Audio::new(Box::new(recording.clone()));
+    let (mut map, mut doors) = map1();
+    // Just north of the stone door at (7, 7), facing it.
+    let player = Player::new(7.5, 6.5, FRAC_PI_2);
+
+    run_doors(&mut doors, &mut audio, 5);
+    let before: Vec<_> = doors.values().map(|d| d.action).collect();
+    assert_eq!(player.use_front(&mut map, &mut doors), UseOutcome::Door);
+    for (sound, x, y) in door_sounds(&before, &doors) {
+        audio.play_at(sound, x, y);
+    }
+    run_doors(&mut doors, &mut audio, 400);
+
+    let open_ticks = (1.0 / DOOR_SPEED).ceil() as u32;
+    let close_tick = 5 + open_ticks + DOOR_OPEN_TICKS;
+    let sounds = recording.sounds();
+    assert_eq!(sounds.first(), Some(&(5, SoundId::DoorOpen)));
+    assert!(sounds.iter().any(|&(tick, s)| s == SoundId::DoorClose && tick.abs_diff(close_tick) <= 1), "{sounds:?}");
+    assert_eq!(sounds.len(), 2, "{sounds:?}");
+}
+
+#[test]
+fn music_changes_are_deduplicated() {
+    let recording = RecordingBackend::default();
+    let mut audio = Audio::new(Box::new(recording.clone()));
+    audio.set_music(Some(Music::Level));
+    audio.advance();
+    audio.set_music(Some(Music::Level));
+    audio.advance();
+    audio.set_music(Some(Music::Intermission));
+    audio.set_music(None);
+    assert_eq!(
+        recording.events(),
+        vec![
+            (0, AudioEvent::Music(Some(Music::Level))),
+            (2, AudioEvent::Music(Some(Music::Intermission))),
+            (2, AudioEvent::Music(None)),
+        ]
+    );
+}
+
+#[test]
+fn mixer_plays_a_sound_then_falls_silent() {
+    let rate = 8000.0;
+    let mut mixer = Mixer::new(rate);
+    mixer.handle(AudioEvent::Sound(SoundId::Pickup, Spatial::CENTER));
+    let secs = SoundId::Pickup.tone().secs;
+    let during: Vec<[f32; 2]> = (0..(secs * rate * 0.5) as usize).map(|_| mixer.next_frame()).collect();
+    assert!(during.iter().any(|f| f[0].abs() > 0.1 && f[1].abs() > 0.1));
+    for _ in 0..(secs * rate) as usize {
+        mixer.next_frame();
+    }
+    assert!((0..100).all(|_| mixer.next_frame() == [0.0, 0.0]));
+}
+
+#[test]
+fn positional_sounds_pan_and_fade_with_the_listener() {
+    // Listener at the origin facing +x; +y is to its right.
+    let listener = (0.0, 0.0, 0.0);
+    let ahead = Spatial::at(listener, 2.0, 0.0);
+    let right = Spatial::at(listener, 0.0, 2.0);
+    let left = Spatial::at(listener, 0.0, -2.0);
+    let far = Spatial::at(listener, 20.0, 0.0);
+
+    assert!(ahead.pan.abs() < 1e-4);
+    assert!(right.pan > 0.99 && left.pan < -0.99);
+    assert!(far.gain < ahead.gain);
+    let [l, r] = right.channel_gains();
+    assert!(r > l);
+
+    // Turning to face the sound centres it.
+    let turned = Spatial::at((0.0, 0.0, FRAC_PI_2), 0.0, 2.0);
+    assert!(turned.pan.abs() < 1e-4);
+
+    let recording = RecordingBackend::default();
+    let mut audio = Audio::new(Box::new(recording.clone()));
+    audio.set_listener(&Player::new(0.0, 0.0, 0.0));
+    audio.play_at(SoundId::Alert, 0.0, -2.0);
+    assert_eq!(recording.events(), vec![(0, AudioEvent::Sound(SoundId::Alert, left))]);
+}
+
+#[test]
+fn locked_doors_deny_with_a_sound_and_a_message() {
+    let mut world = world1();
+    // Just north of the gold door at (12, 13), facing it, with no keys.
+    world.player = Player::new(12.5, 12.5, FRAC_PI_2);
+    let events = world.tick(&TickInput { use_pressed: true, ..TickInput::default() });
+    assert!(events.contains(&Event::Message("YOU NEED THE GOLD KEY".to_string())), "{events:?}");
+    assert!(events.contains(&Event::Sound(SoundId::Denied)), "{events:?}");
+    assert_eq!(SoundId::from_name("denied"), Some(SoundId::Denied));
+}
@@ -0,0 +1,110 @@
+use wolf3d_rs::ai;
+use wolf3d_rs::audio::SoundId;
+use wolf3d_rs::bestiary::{Attack, Bestiary, SpriteSet};
+use wolf3d_rs::difficulty::Difficulty;
+use wolf3d_rs::actor::EnemyHit;
+use wolf3d_rs::entity::EntityId;
+use wolf3d_rs::items::ItemKind;
+use wolf3d_rs::player::Player;
+use wolf3d_rs::world::World;
+
+const WOLF: &str = "
+# A modded kind
+kind = w wolf
+health = 30
+attack = melee 0.08 6 20
+phase = 10 melee 0.12 9 10
+drop = h
+sprite = dog 0.6 0.6 0.6
+sounds = bark knife enemy_death
+score = 300
+";
+
+/// The first enemy of kind `name` in `world`.
+fn first(world: &World, name: &str) -> EntityId {
+    let kind = world.bestiary.find(name).unwrap();
+    world.enemies.iter().find(|(_, e)| e.kind == kind).map(|(id, _)| id).unwrap()
+}
+
+#[test]
+fn parses_a_kind_with_phases() {
+    let bestiary = Bestiary::parse(WOLF).unwrap();
+    let wolf = bestiary.get(bestiary.by_token('w').unwrap());
+    assert_eq!((wolf.name.as_str(), wolf.health, wolf.score), ("wolf", 30, 300));
+    assert_eq!((wolf.drop, wolf.sprite), (Some(ItemKind::FirstAid), SpriteSet::Dog));
+    assert_eq!((wolf.alert_sound, wolf.attack_sound), (SoundId::Bark, SoundId::Knife));
+    assert_eq!(wolf.phase(30).attack, Attack::Melee);
+    assert_eq!(wolf.phase(30).damage, 6);
+    assert_eq!(wolf.phase(11).damage, 6);
+    assert_eq!(wolf.phase(10).damage, 9);
+    assert_eq!(bestiary.find("wolf"), bestiary.by_token('w'));
+}
+
+#[test]
+fn rejects_malformed_kinds() {
+    for (text, why) in [
+        ("health = 5", "setting before any kind"),
+        ("kind = 1 a\nattack = melee 0.1 1 1", "no health"),
+        ("kind = 1 a\nhealth = 5", "no attack"),
+        ("kind = p a\nhealth = 5\nattack = melee 0.1 1 1", "reserved token"),
+        ("kind = 1 a\nhealth = 5\nattack = laser 0.1 1 1", "unknown attack"),
+        ("kind = 1 a\nhealth = 5\nattack = melee 0.1 1 1\ndrop = z", "unknown item"),
+        ("kind = 1 a\nhealth = 5\nattack = melee 0.1 1 1\nsounds = moo moo moo", "unknown sound"),
+        ("kind = 1 a\nhealth = 5\nattack = melee 0.1 1 1\nphase = 9 melee 0.1 1 1", "phase above full health"),
+        ("kind = 1 a\nhealth = 5\nattack = melee 0.1 1 1\nkind = 1 b\nhealth = 5\nattack = melee 0.1 1 1", "duplicate"),
+    ] {
+        assert!(Bestiary::parse(text).is_err(), "accepted {why}");
+    }
+}
+
+#[test]
+fn maps_place_every_built_in_kind() {
+    let world = World::load("map2", Difficulty::Hard).unwrap();
+    for name in ["guard", "ss", "officer", "dog", "mutant", "hans", "schabbs"] {
+        let kind = world.bestiary.find(name).unwrap_or_else(|| panic!("no {name} kind"));
+        assert!(world.enemies.values().any(|e| e.kind == kind), "map2 has no {name}");
+    }
+    for e in world.enemies.values() {
+        assert_eq!(e.health, world.bestiary.get(e.kind).health);
+    }
+}
+
+#[test]
+fn bosses_change_phase_and_drop_on_death() {
+    let mut world = World::load("map2", Difficulty::Medium).unwrap();
+    let hans = first(&world, "hans");
+    let items = world.items.len();
+
+    assert_eq!(world.damage_enemy(hans, 100), EnemyHit::Hurt);
+    assert!(world.enemies[hans].alert);
+    assert_eq!(world.damage_enemy(hans, 400), EnemyHit::NewPhase(1));
+    assert_eq!(world.damage_enemy(hans, 349), EnemyHit::Hurt);
+    assert_eq!(world.items.len(), items);
+    assert_eq!(world.damage_enemy(hans, 20), EnemyHit::Killed);
+    assert!(!world.enemies[hans].alive);
+
+    let drop = world.items.values().last().unwrap();
+    assert_eq!(world.items.len(), items + 1);
+    assert_eq!((drop.kind, drop.x, drop.y, drop.dropped), (ItemKind::GoldKey, 12.5, 12.5, true));
+}
+
+#[test]
+fn alerted_dogs_chase_and_bite() {
+    let mut world = World::load("map2", Difficulty::Medium).unwrap();
+    let dog = first(&world, "dog");
+    world.player = Player::new(7.5, 8.5, 0.0);
+
+    // Not alerted yet, so it stays put.
+    assert!(ai::update(&mut world).is_empty());
+    assert_eq!((world.enemies[dog].x, world.enemies[dog].y), (7.5, 12.5));
+
+    world.enemies[dog].alert = true;
+    let mut bites = 0;
+    for _ in 0..120 {
+        bites += ai::update(&mut world).iter().filter(|&&a| a == dog).count();
+    }
+    let e = &world.enemies[dog];
+    assert!((e.y - world.player.y).abs() <= ai::MELEE_RANGE, "dog stopped at ({}, {})", e.x, e.y);
+    assert!(bites >= 2, "{bites} bites");
+    assert!(world.player.health < 100);
+}
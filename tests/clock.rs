@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use wolf3d_rs::clock::{TickClock, MAX_TICKS_PER_FRAME};
+use wolf3d_rs::episode::TICK_RATE;
+
+/// Ticks run over one second of frames at `hz`.
+fn ticks_in_a_second(hz: u32) -> u32 {
+    let mut clock = TickClock::default();
+    let frame = Duration::from_secs(1) / hz;
+    (0..hz).map(|_| clock.advance(frame)).sum()
+}
+
+#[test]
+fn any_refresh_rate_ticks_at_the_tick_rate() {
+    for hz in [30, 60, 75, 144, 240] {
+        let ticks = ticks_in_a_second(hz);
+        assert!(ticks.abs_diff(TICK_RATE) <= 1, "{hz} Hz ran {ticks} ticks");
+    }
+}
+
+#[test]
+fn fast_frames_carry_their_leftover_time() {
+    let mut clock = TickClock::default();
+    // Seven-millisecond frames, about 144 Hz.
+    let per_frame: Vec<u32> = (0..12).map(|_| clock.advance(Duration::from_millis(7))).collect();
+    assert!(per_frame.iter().all(|&n| n <= 1), "{per_frame:?}");
+    assert_eq!(per_frame.iter().sum::<u32>(), 5, "84 ms is five ticks and a bit");
+    assert_eq!(TickClock::default().advance(Duration::from_secs(1) / 30), 2);
+}
+
+#[test]
+fn stalls_catch_up_only_a_few_ticks() {
+    let mut clock = TickClock::default();
+    assert_eq!(clock.advance(Duration::from_secs(5)), MAX_TICKS_PER_FRAME);
+    assert_eq!(clock.advance(Duration::ZERO), 0, "the rest of the stall is dropped");
+}
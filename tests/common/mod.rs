@@ -0,0 +1,36 @@
+//! Fixtures shared by the integration tests. Each test crate uses only some of them.
+#![allow(dead_code)]
+
+use wolf3d_rs::difficulty::Difficulty;
+use wolf3d_rs::door::Door;
+use wolf3d_rs::entity::Entities;
+use wolf3d_rs::episode::Episode;
+use wolf3d_rs::game::{self, Game};
+use wolf3d_rs::map::Map;
+use wolf3d_rs::world::World;
+
+/// map1's geometry and its doors, all closed.
+pub fn map1() -> (Map, Entities<Door>) {
+    let mut map = Map::load("map1").unwrap();
+    let doors = map.spawn_doors();
+    (map, doors)
+}
+
+/// map1 with its medium-difficulty enemies and items.
+pub fn world1() -> World {
+    World::load("map1", Difficulty::Medium).unwrap()
+}
+
+/// Slides every door `ticks` ticks with nobody in the way, as the world's door system would.
+pub fn slide_doors(doors: &mut Entities<Door>, ticks: u32) {
+    for _ in 0..ticks {
+        for door in doors.values_mut() {
+            door.update(false);
+        }
+    }
+}
+
+/// A medium game at the start of the episode.
+pub fn new_game() -> Game {
+    Game::new(Episode::load(game::EPISODE).unwrap(), Difficulty::Medium).unwrap()
+}
@@ -0,0 +1,55 @@
+use wolf3d_rs::cvar::{CvarValue, Cvars};
+
+#[test]
+fn set_parses_by_type_and_rejects_bad_values() {
+    let mut cvars = Cvars::default();
+    assert_eq!(cvars.set("fov", "75"), Ok(CvarValue::Float(75.0)));
+    assert_eq!(cvars.set("r_resolution", "320"), Ok(CvarValue::Int(320)));
+    assert_eq!(cvars.set("r_floor_color", "0.1 0.2 0.3"), Ok(CvarValue::Color([0.1, 0.2, 0.3])));
+
+    assert_eq!(cvars.set("fov", "wide"), Err("bad value for fov: wide".to_string()));
+    assert!(cvars.set("r_resolution", "1.5").is_err(), "ints take no fraction");
+    assert!(cvars.set("r_floor_color", "0.1 0.2").is_err(), "colours take three channels");
+    assert_eq!(cvars.set("gamma", "1"), Err("unknown cvar gamma".to_string()));
+    assert_eq!(cvars.float("fov"), 75.0, "a failed set keeps the old value");
+}
+
+#[test]
+fn set_clamps_to_the_cvar_range() {
+    let mut cvars = Cvars::default();
+    assert_eq!(cvars.set("fov", "500"), Ok(CvarValue::Float(120.0)));
+    assert_eq!(cvars.set("fov", "-5"), Ok(CvarValue::Float(30.0)));
+    assert_eq!(cvars.set("r_resolution", "1"), Ok(CvarValue::Int(40)));
+    assert_eq!(cvars.set("r_ceiling_color", "2 0.5 -1"), Ok(CvarValue::Color([1.0, 0.5, 0.0])));
+    assert_eq!(cvars.color("r_ceiling_color"), [1.0, 0.5, 0.0, 1.0]);
+}
+
+#[test]
+fn apply_config_reports_bad_lines_and_applies_the_rest() {
+    let mut cvars = Cvars::default();
+    let errors = cvars.apply_config("# comment\n\n  fov 90  \ngamma 2\nmove_speed fast\nr_resolution 640\n");
+    assert_eq!(errors, ["unknown cvar gamma", "bad value for move_speed: fast"]);
+    assert_eq!(cvars.float("fov"), 90.0);
+    assert_eq!(cvars.int("r_resolution"), 640);
+}
+
+#[test]
+fn config_round_trips_only_changed_cvars() {
+    let mut cvars = Cvars::default();
+    assert_eq!(cvars.to_config().lines().count(), 1, "defaults write only the header");
+
+    cvars.set("fov", "80").unwrap();
+    cvars.set("r_floor_color", "0.5 0.25 0").unwrap();
+    cvars.set("r_minimap_tiles", "8").unwrap();
+    let text = cvars.to_config();
+    assert_eq!(text.lines().count(), 4);
+
+    let mut loaded = Cvars::default();
+    assert!(loaded.apply_config(&text).is_empty());
+    for var in cvars.iter() {
+        assert_eq!(loaded.get(var.name).map(|v| v.value), Some(var.value), "{}", var.name);
+    }
+
+    loaded.reset("fov").unwrap();
+    assert!(!loaded.to_config().contains("fov"), "a reset cvar is dropped from the config");
+}
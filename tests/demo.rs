@@ -0,0 +1,91 @@
+mod common;
+
+use wolf3d_rs::demo::{checksum, Demo, Playback};
+use wolf3d_rs::difficulty::Difficulty;
+use wolf3d_rs::game::{parse_inputs, Game};
+use wolf3d_rs::player::{MOVE_SPEED, ROT_SPEED};
+
+use common::new_game;
+
+const SCRIPT: &str = "
+60 forward
+30 left
+10 fire
+1 use
+40
+80 forward right fire
+";
+
+/// Records `SCRIPT` from `seed`, returning the demo and the game as it ended.
+fn record_game(seed: u64) -> (Demo, Game) {
+    let mut demo = Demo::new("map1", Difficulty::Hard, seed, MOVE_SPEED, ROT_SPEED);
+    let mut game = new_game();
+    demo.start(&mut game).unwrap();
+    for input in parse_inputs(SCRIPT).unwrap() {
+        game.tick(&input);
+        demo.record(input, &game);
+    }
+    (demo, game)
+}
+
+fn record(seed: u64) -> Demo {
+    record_game(seed).0
+}
+
+/// Plays `demo` to the end and returns the first desynced tick.
+fn play(demo: Demo) -> Option<usize> {
+    let mut game = new_game();
+    let mut playback = Playback::start(demo, &mut game).unwrap();
+    while playback.tick(&mut game).is_some() {}
+    assert!(playback.finished());
+    playback.desync
+}
+
+#[test]
+fn demos_round_trip_through_text() {
+    let demo = record(12345);
+    assert_eq!(demo.inputs.len(), 221);
+    assert_eq!(demo.checksums.len(), 221);
+    let text = demo.to_text();
+    assert!(text.starts_with("WOLF3D-DEMO 1\n"));
+    assert_eq!(text.lines().filter(|l| l.starts_with("input ")).count(), 6);
+    assert_eq!(Demo::parse(&text).unwrap(), demo);
+
+    assert!(Demo::parse("WOLF3D-DEMO 999\nmap map1\nspeed 0.05 0.04\n").is_err());
+    assert!(Demo::parse("WOLF3D-DEMO 1\nspeed 0.05 0.04\n").is_err());
+    assert!(Demo::parse("WOLF3D-DEMO 1\nmap map1\nspeed 0.05 0.04\ninput 3 jump\n").is_err());
+    assert!(Demo::parse("WOLF3D-DEMO 1\nmap map1\nspeed 0.05 0.04\nchecks 12345\n").is_err());
+}
+
+#[test]
+fn playback_reproduces_the_recording() {
+    let demo = record(12345);
+    let mut game = new_game();
+    let mut playback = Playback::start(demo.clone(), &mut game).unwrap();
+    assert_eq!(game.world.difficulty, Difficulty::Hard);
+    assert_eq!(game.world.rng.state, 12345);
+    while playback.tick(&mut game).is_some() {}
+    assert_eq!(playback.desync, None);
+    assert_eq!(checksum(&game), *demo.checksums.last().unwrap());
+    // Replaying the parsed file works just as well.
+    assert_eq!(play(Demo::parse(&demo.to_text()).unwrap()), None);
+}
+
+#[test]
+fn playback_reports_the_first_desynced_tick() {
+    let mut demo = record(12345);
+    demo.inputs[70].turn_left = false;
+    assert_eq!(play(demo), Some(71));
+
+    let mut demo = record(12345);
+    demo.checksums[150] ^= 1;
+    assert_eq!(play(demo), Some(151));
+
+    // Starting from another seed is a different game: the enemies' shots land and wound
+    // differently, so the same inputs leave the player in another state.
+    let (mut demo, game) = record_game(12345);
+    let (_, other) = record_game(999);
+    assert_ne!(game.world.player.health, other.world.player.health);
+    demo.seed = 999;
+    assert_eq!(play(demo), Some(1));
+}
@@ -0,0 +1,27 @@
+use wolf3d_rs::episode::{Episode, Progress};
+use wolf3d_rs::game;
+
+#[test]
+fn secret_exits_detour_through_hidden_maps_and_return() {
+    let episode = Episode::load(game::EPISODE).unwrap();
+    let index = |name| episode.index_of(name).unwrap();
+    let floor = |p: &Progress| episode.floor(p.index);
+
+    // The normal exit skips the hidden map.
+    let mut p = Progress::start();
+    assert_eq!(floor(&p), 1);
+    assert!(p.advance(&episode, false));
+    assert_eq!((p.index, p.return_to, floor(&p)), (index("map2"), None, 2));
+    // map2 has no secret exit, so its secret switch just ends the episode like the normal one.
+    let mut secret = p;
+    assert!(!secret.advance(&episode, true));
+    assert!(!p.advance(&episode, false));
+
+    // The secret exit goes to secret1, numbered after the regular floors, then back to map2.
+    let mut p = Progress::start();
+    assert!(p.advance(&episode, true));
+    assert_eq!((p.index, p.return_to, floor(&p)), (index("secret1"), Some(index("map1")), 3));
+    assert!(p.advance(&episode, false));
+    assert_eq!((p.index, p.return_to, floor(&p)), (index("map2"), None, 2));
+    assert!(!p.advance(&episode, false));
+}
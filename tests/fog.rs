@@ -0,0 +1,59 @@
+use wolf3d_rs::fog::{Fog, FogMode, NUM_COLORMAPS};
+
+fn fog(mode: FogMode) -> Fog {
+    Fog { mode, ..Fog::default() }
+}
+
+/// `f` sampled every tenth of a tile out to 40 tiles.
+fn samples(f: impl Fn(f32) -> f32) -> Vec<f32> {
+    (0..=400).map(|i| f(i as f32 * 0.1)).collect()
+}
+
+#[test]
+fn linear_fog_runs_from_start_to_end() {
+    let linear = fog(FogMode::Linear { start: 4.0, end: 12.0 });
+    assert_eq!(linear.factor(0.0), 0.0);
+    assert_eq!(linear.factor(4.0), 0.0);
+    assert_eq!(linear.factor(8.0), 0.5);
+    assert_eq!(linear.factor(12.0), 1.0);
+    assert_eq!(linear.factor(30.0), 1.0);
+
+    // A wall of fog at `end` when the range is empty.
+    let wall = fog(FogMode::Linear { start: 6.0, end: 6.0 });
+    assert_eq!((wall.factor(5.9), wall.factor(6.0)), (0.0, 1.0));
+}
+
+#[test]
+fn fog_thickens_with_distance_and_stays_in_range() {
+    let modes = [
+        FogMode::Linear { start: 2.0, end: 10.0 },
+        FogMode::Exponential { density: 0.2 },
+    ];
+    for mode in modes {
+        let factors = samples(|d| fog(mode).factor(d));
+        assert_eq!(factors[0], 0.0, "{mode:?} at the eye");
+        assert!(factors.windows(2).all(|w| w[0] <= w[1]), "{mode:?} thins out somewhere");
+        assert!(factors.iter().all(|f| (0.0..=1.0).contains(f)), "{mode:?} leaves 0..1");
+    }
+    assert!(fog(FogMode::Exponential { density: 0.2 }).factor(40.0) > 0.99);
+    assert_eq!(fog(FogMode::None).factor(1000.0), 0.0);
+}
+
+#[test]
+fn diminishing_steps_down_whole_colormaps() {
+    let bright = fog(FogMode::Diminish { light: 1.0 });
+    let steps = samples(|d| bright.colormap_scale(d));
+    assert_eq!(steps[0], 1.0, "full light at the eye");
+    assert!(steps.windows(2).all(|w| w[0] >= w[1]), "brightens with distance somewhere");
+    for s in &steps {
+        let index = (1.0 - s) * NUM_COLORMAPS as f32;
+        assert!((index - index.round()).abs() < 1e-4, "{s} is between colormaps");
+    }
+    assert_eq!(bright.colormap_scale(0.1), bright.colormap_scale(0.4), "one step covers half a tile");
+    assert_eq!(*steps.last().unwrap(), 1.0 / NUM_COLORMAPS as f32, "darkest colormap");
+
+    // Darker sectors start further down the colormap.
+    let dim = fog(FogMode::Diminish { light: 0.5 });
+    assert_eq!(dim.colormap_scale(0.0), 0.5);
+    assert_eq!(fog(FogMode::Linear { start: 0.0, end: 1.0 }).colormap_scale(5.0), 1.0);
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use std::f32::consts::FRAC_PI_2;
+
+use wolf3d_rs::game::{format_inputs, parse_inputs, Game};
+use wolf3d_rs::player::Player;
+use wolf3d_rs::world::{Event, TickInput};
+
+use common::new_game;
+
+const SCRIPT: &str = "
+# Walk in, look around, fire a burst.
+60 forward
+30 left
+10 fire
+1 use
+40
+";
+
+fn play(inputs: &[TickInput]) -> Game {
+    let mut game = new_game();
+    for input in inputs {
+        game.tick(input);
+    }
+    game
+}
+
+#[test]
+fn input_scripts_round_trip() {
+    let inputs = parse_inputs(SCRIPT).unwrap();
+    assert_eq!(inputs.len(), 141);
+    assert!(inputs[0].forward && !inputs[0].fire);
+    assert!(inputs[100].use_pressed);
+    assert_eq!(inputs[140], TickInput::default());
+    assert_eq!(parse_inputs(&format_inputs(&inputs)).unwrap(), inputs);
+    assert_eq!(format_inputs(&inputs).lines().count(), 5);
+    assert!(parse_inputs("10 jump").is_err());
+    assert!(parse_inputs("forward").is_err());
+}
+
+#[test]
+fn same_inputs_hash_the_same() {
+    let inputs = parse_inputs(SCRIPT).unwrap();
+    let a = play(&inputs);
+    let b = play(&inputs);
+    assert_eq!(a.state_hash(), b.state_hash());
+    assert_ne!(a.state_hash(), new_game().state_hash());
+
+    let mut other = inputs.clone();
+    other[70].turn_left = false;
+    assert_ne!(play(&other).state_hash(), a.state_hash());
+}
+
+#[test]
+fn exit_switch_puts_up_the_intermission_until_continued() {
+    let mut game = new_game();
+    game.world.player = Player::new(11.5, 11.5, -FRAC_PI_2);
+    let events = game.tick(&TickInput { use_pressed: true, ..TickInput::default() });
+    assert!(events.iter().any(|e| matches!(e, Event::Exit(_))));
+    let stats = game.intermission.expect("level over");
+    assert_eq!((stats.floor, stats.episode_done), (1, false));
+
+    // Ticks are ignored until the player moves on.
+    let hash = game.state_hash();
+    assert!(game.tick(&TickInput { forward: true, ..TickInput::default() }).is_empty());
+    assert_eq!(game.state_hash(), hash);
+
+    assert!(game.continue_from_intermission().unwrap());
+    assert_eq!((game.world.name.as_str(), game.floor()), ("map2", 2));
+    assert!(game.intermission.is_none());
+}
@@ -0,0 +1,89 @@
+use wolf3d_rs::difficulty::Difficulty;
+use wolf3d_rs::entity::Entities;
+use wolf3d_rs::items::{pick_up, touch_items, Item, ItemKind, MAX_AMMO};
+use wolf3d_rs::player::{Key, Player, Weapon, MAX_HEALTH, MAX_LIVES, START_AMMO, START_LIVES};
+
+fn player() -> Player {
+    Player::new(2.5, 2.5, 0.0)
+}
+
+#[test]
+fn health_is_refused_at_full_health_and_capped() {
+    let mut p = player();
+    assert!(!pick_up(&mut p, ItemKind::FirstAid, Difficulty::Medium));
+    assert!(!pick_up(&mut p, ItemKind::DogFood, Difficulty::Medium));
+    assert_eq!(p.health, MAX_HEALTH);
+
+    p.health = 50;
+    assert!(pick_up(&mut p, ItemKind::DogFood, Difficulty::Medium));
+    assert!(pick_up(&mut p, ItemKind::Food, Difficulty::Medium));
+    assert_eq!(p.health, 64);
+    p.health = 90;
+    assert!(pick_up(&mut p, ItemKind::FirstAid, Difficulty::Medium));
+    assert_eq!(p.health, MAX_HEALTH);
+}
+
+#[test]
+fn ammo_is_refused_when_full_and_scaled_by_difficulty() {
+    let mut p = player();
+    assert!(pick_up(&mut p, ItemKind::Clip, Difficulty::Medium));
+    assert_eq!(p.ammo, START_AMMO + 8);
+    assert!(pick_up(&mut p, ItemKind::Clip, Difficulty::Baby));
+    assert_eq!(p.ammo, START_AMMO + 8 + 16);
+
+    p.ammo = MAX_AMMO - 3;
+    assert!(pick_up(&mut p, ItemKind::Clip, Difficulty::Medium));
+    assert_eq!(p.ammo, MAX_AMMO);
+    assert!(!pick_up(&mut p, ItemKind::Clip, Difficulty::Medium));
+}
+
+#[test]
+fn keys_treasure_and_weapons() {
+    let mut p = player();
+    assert!(!p.has_key(Key::Gold));
+    assert!(pick_up(&mut p, ItemKind::GoldKey, Difficulty::Medium));
+    assert!(p.has_key(Key::Gold) && !p.has_key(Key::Silver));
+    assert!(pick_up(&mut p, ItemKind::SilverKey, Difficulty::Medium));
+    assert!(p.has_key(Key::Silver));
+
+    for (kind, score) in [(ItemKind::Cross, 100), (ItemKind::Chalice, 600), (ItemKind::Chest, 1600), (ItemKind::Crown, 6600)] {
+        assert!(pick_up(&mut p, kind, Difficulty::Medium));
+        assert_eq!(p.score, score, "{kind:?}");
+        assert!(kind.is_treasure());
+    }
+
+    assert!(pick_up(&mut p, ItemKind::Chaingun, Difficulty::Medium));
+    assert!(pick_up(&mut p, ItemKind::MachineGun, Difficulty::Medium));
+    assert_eq!(p.weapon, Weapon::Chaingun, "never a downgrade");
+    assert_eq!(p.ammo, START_AMMO + 12);
+}
+
+#[test]
+fn extra_lives_heal_and_stop_at_the_cap() {
+    let mut p = player();
+    p.health = 10;
+    assert!(pick_up(&mut p, ItemKind::ExtraLife, Difficulty::Medium));
+    assert_eq!((p.lives, p.health, p.ammo), (START_LIVES + 1, MAX_HEALTH, START_AMMO + 25));
+
+    p.lives = MAX_LIVES;
+    p.health = 10;
+    assert!(pick_up(&mut p, ItemKind::ExtraLife, Difficulty::Medium));
+    assert_eq!((p.lives, p.health), (MAX_LIVES, MAX_HEALTH));
+}
+
+#[test]
+fn touching_collects_only_usable_items_on_the_tile() {
+    let item = |kind, x, y| Item { kind, x, y, picked: false, dropped: false };
+    let mut items: Entities<Item> = [item(ItemKind::FirstAid, 2.5, 2.5), item(ItemKind::Cross, 2.5, 2.5), item(ItemKind::Crown, 3.5, 2.5)]
+        .into_iter()
+        .collect();
+    let mut p = player();
+    assert_eq!(touch_items(&mut items, &mut p, Difficulty::Medium), vec![ItemKind::Cross]);
+    assert_eq!(p.bonus_flash, 1.0);
+    assert_eq!(items.values().map(|i| i.picked).collect::<Vec<_>>(), vec![false, true, false]);
+
+    // Hurt, the first aid is worth taking; the cross is gone.
+    p.health = 40;
+    assert_eq!(touch_items(&mut items, &mut p, Difficulty::Medium), vec![ItemKind::FirstAid]);
+    assert_eq!((p.health, p.score), (65, 100));
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use wolf3d_rs::entity::Entities;
+use wolf3d_rs::light::{LightEffect, Lighting, PointLight, TileLight};
+use wolf3d_rs::map::Map;
+
+use common::map1;
+
+/// map1 with every sector pitch black, so only point lights show.
+fn dark_map1() -> Map {
+    let (mut map, _) = map1();
+    for row in map.light_levels.iter_mut() {
+        row.fill(TileLight { level: 0.0, effect: LightEffect::Steady });
+    }
+    map
+}
+
+fn white_light(x: f32, y: f32, radius: f32) -> PointLight {
+    PointLight { x, y, radius, color: [1.0; 3], effect: LightEffect::Steady, ttl: None, lifetime: 0 }
+}
+
+fn lit(lights: &[PointLight]) -> Lighting {
+    Lighting { tick: 0, point_lights: lights.iter().copied().collect::<Entities<_>>() }
+}
+
+#[test]
+fn point_lights_fall_off_linearly_to_their_radius() {
+    let map = dark_map1();
+    let lighting = lit(&[white_light(8.5, 8.5, 4.0)]);
+    let at = |dx: f32| lighting.light_at(&map, 8.5 + dx, 8.5)[0];
+    assert_eq!(at(0.0), 1.0);
+    assert_eq!(at(1.0), 0.75);
+    assert_eq!(at(2.0), 0.5);
+    assert_eq!(at(4.0), 0.0);
+    assert_eq!(at(6.0), 0.0);
+
+    let tinted = lit(&[PointLight { color: [1.0, 0.5, 0.0], ..white_light(8.5, 8.5, 4.0) }]);
+    assert_eq!(tinted.light_at(&map, 8.5, 10.5), [0.5, 0.25, 0.0]);
+}
+
+#[test]
+fn overlapping_lights_add_up_to_a_clamp() {
+    let map = dark_map1();
+    let two = lit(&[white_light(8.5, 8.5, 4.0), white_light(9.5, 8.5, 4.0)]);
+    assert_eq!(two.light_at(&map, 9.0, 8.5), [1.5; 3], "0.875 from each");
+    assert_eq!(two.light_at(&map, 6.5, 8.5), [0.5 + 0.25; 3]);
+
+    let pile = lit(&[white_light(8.5, 8.5, 4.0); 6]);
+    assert_eq!(pile.light_at(&map, 8.5, 8.5), [1.5; 3]);
+
+    // Sector light counts towards the clamp too.
+    let (bright, _) = map1();
+    assert_eq!(lit(&[white_light(2.5, 2.5, 2.0)]).light_at(&bright, 2.5, 2.5), [1.5; 3]);
+}
+
+#[test]
+fn short_lived_lights_fade_and_despawn() {
+    let map = dark_map1();
+    let mut lighting = lit(&[]);
+    lighting.spawn(PointLight::muzzle_flash(8.5, 8.5));
+    let mut seen = Vec::new();
+    while !lighting.point_lights.is_empty() {
+        seen.push(lighting.light_at(&map, 8.5, 8.5)[0]);
+        lighting.update();
+    }
+    assert_eq!(seen, [1.0, 0.75, 0.5, 0.25, 0.0]);
+}
+
+#[test]
+fn animated_tiles_stay_between_their_low_and_their_level() {
+    for effect in [LightEffect::Flicker, LightEffect::Pulse, LightEffect::Strobe] {
+        let light = TileLight { level: 0.8, effect };
+        let low = 0.8 * 0.3;
+        let values: Vec<f32> = (0..400).flat_map(|tick| (0..4).map(move |t| light.brightness(tick, t, 3 - t))).collect();
+        assert!(values.iter().all(|v| (low - 1e-6..=0.8 + 1e-6).contains(v)), "{effect:?} out of range");
+        let (min, max) = values.iter().fold((f32::MAX, f32::MIN), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+        assert!(min < low + 0.01 && max > 0.79, "{effect:?} only spans {min}..{max}");
+    }
+    let steady = TileLight { level: 0.6, effect: LightEffect::Steady };
+    assert!((0..100).all(|tick| steady.brightness(tick, 5, 5) == 0.6));
+}
@@ -0,0 +1,27 @@
+use wolf3d_rs::cvar::Cvars;
+use wolf3d_rs::minimap::{MinimapConfig, MinimapEnemies};
+
+#[test]
+fn small_windows_scroll_with_the_player_but_stop_at_the_edges() {
+    let cfg = MinimapConfig { view_tiles: 8, ..MinimapConfig::default() };
+    assert_eq!(cfg.scroll(2.5, 3.0), (0.0, 0.0));
+    assert_eq!(cfg.scroll(8.5, 6.0), (4.5, 2.0));
+    assert_eq!(cfg.scroll(15.5, 14.0), (8.0, 8.0));
+
+    // A window as big as the map never scrolls.
+    let whole = MinimapConfig { view_tiles: 16, ..cfg };
+    assert_eq!(whole.scroll(8.5, 14.0), (0.0, 0.0));
+}
+
+#[test]
+fn cvars_place_and_size_the_minimap() {
+    let mut cvars = Cvars::default();
+    let cfg = MinimapConfig::from_cvars(&cvars, MinimapEnemies::All);
+    assert_eq!(cfg, MinimapConfig { enemies: MinimapEnemies::All, ..MinimapConfig::default() });
+
+    let errors = cvars.apply_config("r_minimap_x 20\nr_minimap_y 30\nr_minimap_size 6\nr_minimap_tiles 2\nr_minimap_opacity 0.5\n");
+    assert!(errors.is_empty(), "{errors:?}");
+    let cfg = MinimapConfig::from_cvars(&cvars, MinimapEnemies::Hidden);
+    assert_eq!((cfg.x, cfg.y, cfg.tile_px, cfg.opacity), (20.0, 30.0, 6.0, 0.5));
+    assert_eq!(cfg.view_tiles, 4, "clamped to the smallest window");
+}
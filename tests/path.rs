@@ -0,0 +1,100 @@
+mod common;
+
+use wolf3d_rs::door::Door;
+use wolf3d_rs::entity::Entities;
+use wolf3d_rs::map::Map;
+use wolf3d_rs::path::{find_path, FlowCache, FlowField, DIAGONAL_COST, STEP_COST};
+
+use common::{map1, slide_doors};
+
+/// Checks every step is to a neighbouring tile, never into a wall, and never cuts a corner.
+fn assert_walkable(map: &Map, doors: &Entities<Door>, from: (i32, i32), path: &[(i32, i32)]) {
+    let mut at = from;
+    for &next in path {
+        let (dx, dy) = (next.0 - at.0, next.1 - at.1);
+        assert!(dx.abs() <= 1 && dy.abs() <= 1 && (dx, dy) != (0, 0), "{at:?} -> {next:?}");
+        assert!(!map.is_solid(doors, next.0, next.1) || map.is_door(next.0, next.1), "into a wall at {next:?}");
+        if dx != 0 && dy != 0 {
+            for side in [(at.0 + dx, at.1), (at.0, at.1 + dy)] {
+                assert!(!map.is_solid(doors, side.0, side.1) && !map.is_door(side.0, side.1), "cut the corner at {side:?}");
+            }
+        }
+        at = next;
+    }
+}
+
+#[test]
+fn routes_around_walls_without_cutting_corners() {
+    let (map, doors) = map1();
+    let path = find_path(&map, &doors, (1, 1), (14, 14)).expect("opposite corners are connected");
+    assert_eq!(path.last(), Some(&(14, 14)));
+    assert_walkable(&map, &doors, (1, 1), &path);
+    // A* and the flow field agree on the cheapest cost.
+    let mut at = (1, 1);
+    let mut cost = 0;
+    for &next in &path {
+        assert!(!map.is_door(next.0, next.1));
+        cost += if next.0 != at.0 && next.1 != at.1 { DIAGONAL_COST } else { STEP_COST };
+        at = next;
+    }
+    assert_eq!(Some(cost), FlowField::build(&map, &doors, (14, 14)).cost(1, 1));
+
+    assert_eq!(find_path(&map, &doors, (4, 4), (4, 4)), Some(vec![]));
+    assert_eq!(find_path(&map, &doors, (1, 1), (0, 0)), None);
+}
+
+#[test]
+fn closed_doors_are_passable_at_a_cost() {
+    let (map, mut doors) = map1();
+    // The exit room's only way in is the gold door at (12, 13).
+    let path = find_path(&map, &doors, (12, 14), (11, 11)).expect("path through the closed door");
+    assert!(path.contains(&(12, 13)));
+    assert_walkable(&map, &doors, (12, 14), &path);
+
+    let closed = FlowField::build(&map, &doors, (11, 11)).cost(12, 14).unwrap();
+    map.door_at_mut(&mut doors, 12, 13).unwrap().open = 1.0;
+    let open = FlowField::build(&map, &doors, (11, 11)).cost(12, 14).unwrap();
+    assert!(open < closed);
+}
+
+#[test]
+fn following_the_flow_field_reaches_the_target() {
+    let (map, doors) = map1();
+    let field = FlowField::build(&map, &doors, (11, 11));
+    let (mut x, mut y) = (1, 14);
+    let mut steps = Vec::new();
+    while let Some((dx, dy)) = field.direction(&map, &doors, x, y) {
+        (x, y) = (x + dx, y + dy);
+        steps.push((x, y));
+        assert!(steps.len() < 64, "flow field loops");
+    }
+    assert_eq!((x, y), (11, 11));
+    assert_walkable(&map, &doors, (1, 14), &steps);
+    assert_eq!(field.cost(0, 0), None);
+}
+
+#[test]
+fn flow_cache_rebuilds_only_on_changes() {
+    let (mut map, mut doors) = map1();
+    let mut cache = FlowCache::default();
+    cache.toward(&map, &doors, (2, 2));
+    cache.toward(&map, &doors, (2, 2));
+    assert_eq!(cache.builds(), 1);
+
+    // A door sliding part way does not change routes; one opening fully does.
+    map.door_at_mut(&mut doors, 7, 7).unwrap().activate();
+    slide_doors(&mut doors, 1);
+    cache.toward(&map, &doors, (2, 2));
+    assert_eq!(cache.builds(), 1);
+    slide_doors(&mut doors, 40);
+    cache.toward(&map, &doors, (2, 2));
+    assert_eq!(cache.builds(), 2);
+
+    let (x, y) = (map.pushwalls[0].x, map.pushwalls[0].y);
+    assert!(map.push_wall(&doors, x, y, (1, 0)));
+    cache.toward(&map, &doors, (2, 2));
+    assert_eq!(cache.builds(), 3);
+
+    cache.toward(&map, &doors, (3, 2));
+    assert_eq!(cache.builds(), 4);
+}
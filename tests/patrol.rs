@@ -0,0 +1,105 @@
+mod common;
+
+use wolf3d_rs::actor::{ActorState, Dir, PATROL_SPEED};
+use wolf3d_rs::entity::EntityId;
+use wolf3d_rs::world::World;
+
+use common::{slide_doors, world1};
+
+fn enemy_at(world: &World, x: i32, y: i32) -> EntityId {
+    world
+        .enemies
+        .iter()
+        .find(|(_, e)| e.tile() == (x, y))
+        .map(|(id, _)| id)
+        .unwrap_or_else(|| panic!("no enemy at ({x}, {y})"))
+}
+
+fn ticks_per_tile() -> u32 {
+    (1.0 / PATROL_SPEED) as u32
+}
+
+#[test]
+fn actor_tokens_carry_facing_and_state() {
+    let world = world1();
+    let guard = &world.enemies[enemy_at(&world, 5, 5)];
+    assert_eq!(world.bestiary.get(guard.kind).name, "guard");
+    assert_eq!((guard.facing, guard.state), (Dir::East, ActorState::Patrol));
+    let ss = &world.enemies[enemy_at(&world, 13, 9)];
+    assert_eq!(world.bestiary.get(ss.kind).name, "ss");
+    assert_eq!((ss.facing, ss.state), (Dir::West, ActorState::Ambush));
+    let officer = &world.enemies[enemy_at(&world, 11, 11)];
+    assert_eq!((officer.facing, officer.state), (Dir::South, ActorState::Standing));
+
+    assert_eq!(world.map.turns[5][9], Some(Dir::East));
+    assert_eq!(world.map.turns[5][11], Some(Dir::South));
+    assert_eq!(world.map.turns[9][11], Some(Dir::West));
+    assert_eq!(world.map.turns[9][9], Some(Dir::North));
+    assert_eq!(world.map.turns[0][0], None);
+}
+
+#[test]
+fn patrols_follow_turn_markers_around_a_loop() {
+    let mut world = world1();
+    let guard = enemy_at(&world, 5, 5);
+    let tile = ticks_per_tile();
+    // East to the first marker, then round the 2 x 4 loop of markers.
+    let route = [(6, (11.5, 5.5)), (4, (11.5, 9.5)), (2, (9.5, 9.5)), (4, (9.5, 5.5)), (2, (11.5, 5.5))];
+    for (tiles, pos) in route {
+        for _ in 0..tiles * tile {
+            world.update_patrols();
+        }
+        let e = &world.enemies[guard];
+        assert_eq!((e.x, e.y), pos);
+    }
+}
+
+#[test]
+fn patrols_open_doors_and_turn_back_at_walls() {
+    let mut world = world1();
+    let i = enemy_at(&world, 5, 5);
+    let e = &mut world.enemies[i];
+    (e.x, e.y, e.facing) = (7.5, 6.5, Dir::South);
+
+    // Facing the closed door at (7, 7): it opens the door and waits for it.
+    world.update_patrols();
+    slide_doors(&mut world.doors, 1);
+    assert_eq!((world.enemies[i].x, world.enemies[i].y), (7.5, 6.5));
+    assert!(world.map.door_open(&world.doors, 7, 7) > 0.0);
+
+    for _ in 0..9 * ticks_per_tile() {
+        world.update_patrols();
+        slide_doors(&mut world.doors, 1);
+    }
+    // Down the corridor to the wall at the bottom of the map, then back.
+    let e = &world.enemies[i];
+    assert_eq!(e.x, 7.5);
+    assert!(e.y > 8.5);
+    for _ in 0..8 * ticks_per_tile() {
+        world.update_patrols();
+    }
+    assert_eq!(world.enemies[i].facing, Dir::North);
+}
+
+#[test]
+fn alerted_and_standing_enemies_stay_put() {
+    let mut world = world1();
+    let guard = enemy_at(&world, 5, 5);
+    let officer = enemy_at(&world, 11, 11);
+    world.enemies[guard].alert = true;
+    for _ in 0..100 {
+        world.update_patrols();
+    }
+    assert_eq!((world.enemies[guard].x, world.enemies[guard].y), (5.5, 5.5));
+    assert_eq!((world.enemies[officer].x, world.enemies[officer].y), (11.5, 11.5));
+}
+
+#[test]
+fn ambushers_ignore_gunfire_but_see_what_is_in_front() {
+    let mut world = world1();
+    let ss = enemy_at(&world, 13, 9);
+    assert!(!world.alert_enemies(12.5, 9.5).contains(&ss));
+    // Behind it, then in front of it.
+    assert!(!world.sight_alerts(14.5, 9.5).contains(&ss));
+    assert!(world.sight_alerts(10.5, 9.5).contains(&ss));
+}
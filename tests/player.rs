@@ -0,0 +1,87 @@
+mod common;
+
+use wolf3d_rs::difficulty::Difficulty;
+use wolf3d_rs::game::Game;
+use wolf3d_rs::player::{Key, LifeState, Player, DEATH_TICKS, MAX_HEALTH, PAIN_COOLDOWN};
+use wolf3d_rs::world::{Event, TickInput};
+
+use common::{map1, new_game};
+
+#[test]
+fn armour_absorbs_a_third_and_difficulty_scales_hits() {
+    let mut p = Player::new(2.5, 2.5, 0.0);
+    p.armor = 5;
+    assert!(p.damage(30, Difficulty::Medium));
+    // A third of 30 is 10, but only 5 armour is left to soak it.
+    assert_eq!((p.health, p.armor), (75, 0));
+
+    for (difficulty, health) in [(Difficulty::Baby, 95), (Difficulty::Easy, 90), (Difficulty::Hard, 75)] {
+        let mut p = Player::new(2.5, 2.5, 0.0);
+        assert!(p.damage(20, difficulty));
+        assert_eq!(p.health, health, "{difficulty:?}");
+    }
+
+    // Even the easiest difficulty never rounds a hit down to nothing.
+    let mut p = Player::new(2.5, 2.5, 0.0);
+    assert!(p.damage(1, Difficulty::Baby));
+    assert_eq!(p.health, MAX_HEALTH - 1);
+
+    p.god = true;
+    p.pain_cooldown = 0;
+    assert!(!p.damage(50, Difficulty::Medium));
+    assert_eq!(p.health, MAX_HEALTH - 1);
+}
+
+#[test]
+fn pain_cooldown_ignores_hits_until_it_runs_out() {
+    let (map, doors) = map1();
+    let mut p = Player::new(2.5, 2.5, 0.0);
+    assert!(p.damage(10, Difficulty::Medium));
+    assert!(p.damage_flash > 0.0);
+    assert!(!p.damage(10, Difficulty::Medium));
+    for _ in 0..PAIN_COOLDOWN - 1 {
+        p.update(&TickInput::default(), &map, &doors, 0.0, 0.0);
+        assert!(!p.damage(10, Difficulty::Medium));
+    }
+    p.update(&TickInput::default(), &map, &doors, 0.0, 0.0);
+    assert!(p.damage(10, Difficulty::Medium));
+    assert_eq!(p.health, 80);
+}
+
+/// Runs `ticks` idle ticks of `game`.
+fn idle(game: &mut Game, ticks: u32) -> Vec<Event> {
+    (0..ticks).flat_map(|_| game.tick(&TickInput::default())).collect()
+}
+
+#[test]
+fn dying_respawns_until_the_lives_run_out() {
+    let mut game = new_game();
+    let spawn = game.world.map.player_start;
+    let p = &mut game.world.player;
+    p.lives = 1;
+    p.score = 1234;
+    p.give_key(Key::Gold);
+    (p.x, p.y) = (5.5, 2.5);
+
+    assert!(p.damage(500, Difficulty::Medium));
+    assert_eq!((p.health, p.state), (0, LifeState::Dying { ticks: 0 }));
+    idle(&mut game, DEATH_TICKS - 1);
+    assert!(matches!(game.world.player.state, LifeState::Dying { .. }));
+
+    // The last tick of the death sequence spends a life and puts the player back at the spawn.
+    idle(&mut game, 1);
+    let p = &game.world.player;
+    assert!(p.is_alive());
+    assert_eq!((p.x, p.y, p.angle), spawn);
+    assert_eq!((p.lives, p.score, p.health), (0, 1234, MAX_HEALTH));
+    assert!(!p.has_key(Key::Gold), "keys are lost on death");
+
+    // With no lives left, the next death is the end.
+    let p = &mut game.world.player;
+    assert!(p.damage(500, Difficulty::Medium));
+    idle(&mut game, DEATH_TICKS);
+    assert!(game.world.player.is_game_over());
+    idle(&mut game, 10);
+    assert!(game.world.player.is_game_over());
+    assert_eq!(game.world.player.score, 1234);
+}
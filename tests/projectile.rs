@@ -0,0 +1,108 @@
+mod common;
+
+use wolf3d_rs::actor::EnemyHit;
+use wolf3d_rs::ai;
+use wolf3d_rs::difficulty::Difficulty;
+use wolf3d_rs::entity::EntityId;
+use wolf3d_rs::player::Player;
+use wolf3d_rs::projectile::{self, Owner, Projectile, ProjectileKind, Struck};
+use wolf3d_rs::world::World;
+
+use common::world1;
+
+/// map1 with only the first two enemies left alive, moved to `a` and `b`, and the player at `player`.
+fn two_enemies(a: (f32, f32), b: (f32, f32), player: (f32, f32)) -> (World, [EntityId; 2]) {
+    let mut world = world1();
+    world.player = Player::new(player.0, player.1, 0.0);
+    for e in world.enemies.values_mut() {
+        e.alive = false;
+    }
+    for (e, (x, y)) in world.enemies.values_mut().zip([a, b]) {
+        (e.x, e.y, e.alive) = (x, y, true);
+    }
+    let ids = world.enemies.ids();
+    (world, [ids[0], ids[1]])
+}
+
+/// Runs projectile updates until the first detonation.
+fn fly(world: &mut World) -> projectile::Detonation {
+    for _ in 0..400 {
+        if let Some(blast) = projectile::update(world).into_iter().next() {
+            return blast;
+        }
+    }
+    panic!("projectile never hit anything");
+}
+
+#[test]
+fn rockets_strike_walls_without_tunnelling() {
+    let (mut world, _) = two_enemies((1.5, 14.5), (2.5, 14.5), (1.5, 1.5));
+    // Along row 8 toward the wall at (6, 8), starting so the last step would carry it deep inside.
+    world.projectiles.spawn(Projectile::aimed(ProjectileKind::Rocket, Owner::Player, 30, (1.63, 8.5), (9.0, 8.5)));
+    let blast = fly(&mut world);
+    assert_eq!(blast.struck, Struck::Wall);
+    assert!(blast.x < 6.0 && blast.x > 5.9, "blast at {}", blast.x);
+    assert!(world.projectiles.is_empty());
+}
+
+#[test]
+fn projectiles_hit_the_first_enemy_in_their_path() {
+    let (mut world, [near, far]) = two_enemies((4.5, 2.5), (8.5, 2.5), (1.5, 2.5));
+    let health = world.enemies[near].health;
+    world.projectiles.spawn(Projectile::aimed(ProjectileKind::Syringe, Owner::Player, 10, (1.5, 2.5), (9.5, 2.5)));
+    let blast = fly(&mut world);
+    assert_eq!(blast.struck, Struck::Enemy(near));
+    assert_eq!(blast.enemies, vec![(near, EnemyHit::Hurt)]);
+    assert_eq!(world.enemies[near].health, health - 10);
+    assert_eq!(world.enemies[far].health, world.bestiary.get(world.enemies[far].kind).health);
+}
+
+#[test]
+fn walls_shelter_actors_from_splash() {
+    // The blast lands against the west face of (6, 8). Enemy 0 is in the open beside it,
+    // enemy 1 just as close but inside the corridor behind the wall.
+    let (mut world, [open, sheltered]) = two_enemies((5.5, 7.5), (7.2, 8.5), (1.5, 1.5));
+    let (full0, full1) = (world.enemies[open].health, world.enemies[sheltered].health);
+    world.projectiles.spawn(Projectile::aimed(ProjectileKind::Rocket, Owner::Player, 60, (3.0, 8.5), (9.0, 8.5)));
+    let blast = fly(&mut world);
+    assert_eq!(blast.struck, Struck::Wall);
+    assert!(world.enemies[open].health < full0 || !world.enemies[open].alive);
+    assert_eq!(world.enemies[sheltered].health, full1);
+    assert_eq!(blast.enemies.len(), 1);
+    assert_eq!(blast.player_damage, 0);
+}
+
+#[test]
+fn splash_hurts_the_player_less_with_distance() {
+    let damage_at = |px: f32| {
+        let (mut world, _) = two_enemies((1.5, 14.5), (2.5, 14.5), (px, 8.5));
+        // Fired past the player, at the wall (6, 7).
+        world.projectiles.spawn(Projectile::aimed(ProjectileKind::Rocket, Owner::Player, 60, (3.0, 7.5), (9.0, 7.5)));
+        let blast = fly(&mut world);
+        assert_eq!(blast.struck, Struck::Wall);
+        blast.player_damage
+    };
+    let near = damage_at(5.5);
+    let far = damage_at(5.2);
+    assert!(near > far && far > 0, "near {near}, far {far}");
+    assert_eq!(damage_at(2.0), 0);
+}
+
+#[test]
+fn bosses_launch_syringes_that_hit_the_player() {
+    let mut world = World::load("map2", Difficulty::Medium).unwrap();
+    let schabbs = world.bestiary.find("schabbs").unwrap();
+    let id = world.enemies.iter().find(|(_, e)| e.kind == schabbs).unwrap().0;
+    world.enemies[id].alert = true;
+    world.player = Player::new(2.5, 4.5, 0.0);
+
+    assert_eq!(ai::update(&mut world), vec![id]);
+    assert_eq!(world.projectiles.len(), 1);
+    let shot = world.projectiles.values().next().unwrap();
+    assert_eq!((shot.kind, shot.owner), (ProjectileKind::Syringe, Owner::Enemy(id)));
+    assert_eq!(world.player.health, 100, "projectiles take time to arrive");
+
+    let blast = fly(&mut world);
+    assert_eq!(blast.struck, Struck::Player);
+    assert!(world.player.health < 100);
+}
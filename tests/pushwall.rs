@@ -0,0 +1,102 @@
+mod common;
+
+use wolf3d_rs::map::{Cell, Map};
+use wolf3d_rs::path::find_path;
+use wolf3d_rs::player::Player;
+use wolf3d_rs::pushwall::{PushWallState, PUSHWALL_DISTANCE, PUSHWALL_SPEED};
+use wolf3d_rs::world::TickInput;
+
+use common::{map1, world1};
+
+/// map1's push wall at (12, 4), between a corridor to the west and one to the east.
+const PUSHWALL: (i32, i32) = (12, 4);
+
+/// Ticks every moving push wall until all have settled.
+fn settle(map: &mut Map) {
+    let ticks = (PUSHWALL_DISTANCE / PUSHWALL_SPEED).ceil() as usize + 1;
+    for _ in 0..ticks {
+        map.update_pushwalls();
+    }
+    assert!(map.pushwalls.iter().all(|p| !p.is_moving()));
+}
+
+#[test]
+fn pushed_walls_slide_their_full_distance_and_count_as_secrets() {
+    let (mut map, doors) = map1();
+    let (x, y) = PUSHWALL;
+    assert_eq!((map.secrets_found(), map.secrets_total()), (0, 1));
+    assert!(!map.push_wall(&doors, x, y, (0, 1)), "the wall below is in the way");
+    assert!(map.push_wall(&doors, x, y, (1, 0)));
+    assert_eq!(map.secrets_found(), 1, "found as soon as it moves");
+    assert!(!map.push_wall(&doors, x, y, (1, 0)), "only once");
+
+    settle(&mut map);
+    let pw = &map.pushwalls[0];
+    assert_eq!((pw.state, pw.offset), (PushWallState::Done, PUSHWALL_DISTANCE));
+    assert_eq!(map.cell_at(x, y), Cell::Empty);
+    assert_eq!(map.cell_at(x + 1, y), Cell::Empty);
+    assert_eq!(map.cell_at(x + 2, y), Cell::Wall(pw.wall));
+    assert_eq!(map.secrets_found(), 1);
+}
+
+#[test]
+fn pushed_walls_stop_short_of_a_wall() {
+    let (mut map, doors) = map1();
+    let (x, y) = PUSHWALL;
+    map.walls[y as usize][x as usize + 2] = Cell::Wall(2);
+    assert!(map.push_wall(&doors, x, y, (1, 0)));
+    settle(&mut map);
+    let pw = &map.pushwalls[0];
+    assert_eq!((pw.state, pw.offset), (PushWallState::Done, 1.0));
+    assert_eq!(map.cell_at(x + 1, y), Cell::Wall(pw.wall));
+}
+
+#[test]
+fn sliding_walls_block_the_player_and_actors() {
+    let mut world = world1();
+    let (x, y) = PUSHWALL;
+    assert!(world.map.push_wall(&world.doors, x, y, (1, 0)));
+    for _ in 0..32 {
+        world.map.update_pushwalls();
+    }
+    // Half a tile along, the block spans both tiles.
+    for tile in [(x, y), (x + 1, y)] {
+        assert!(world.map.is_solid(&world.doors, tile.0, tile.1), "{tile:?}");
+    }
+    assert_eq!(find_path(&world.map, &world.doors, (x - 1, y), (x + 1, y)), None);
+
+    world.player = Player::new(x as f32 - 0.5, y as f32 + 0.5, 0.0);
+    for _ in 0..30 {
+        world.tick(&TickInput { forward: true, ..TickInput::default() });
+    }
+    assert!(world.player.x < x as f32, "walked into the push wall at x = {}", world.player.x);
+}
+
+#[test]
+fn rays_report_push_walls_in_the_tile_they_meet_them() {
+    let (mut map, doors) = map1();
+    let (x, y) = PUSHWALL;
+    assert!(map.push_wall(&doors, x, y, (1, 0)));
+    for _ in 0..40 {
+        map.update_pushwalls();
+    }
+    let mut hits = 0;
+    for origin in [(10.5, 4.5), (11.2, 4.9), (14.5, 4.5), (13.9, 4.1)] {
+        for i in 0..720 {
+            let angle = i as f32 / 720.0 * std::f32::consts::TAU;
+            let Some(hit) = map.raycast(&doors, origin, (angle.cos(), angle.sin()), 100.0) else { continue };
+            if map.cell_at(hit.map_x, hit.map_y) != Cell::Empty {
+                continue;
+            }
+            hits += 1;
+            let (px, py) = hit.point;
+            let (tx, ty) = (hit.map_x as f32, hit.map_y as f32);
+            assert!(
+                px >= tx - 1e-3 && px <= tx + 1.001 && py >= ty - 1e-3 && py <= ty + 1.001,
+                "ray at {angle} from {origin:?} hit {:?} outside tile ({tx}, {ty})",
+                hit.point
+            );
+        }
+    }
+    assert!(hits > 0);
+}
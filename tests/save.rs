@@ -0,0 +1,150 @@
+mod common;
+
+use wolf3d_rs::actor::Dir;
+use wolf3d_rs::difficulty::Difficulty;
+use wolf3d_rs::episode::Progress;
+use wolf3d_rs::map::Cell;
+use wolf3d_rs::player::{LifeState, Player, Weapon};
+use wolf3d_rs::projectile::{Owner, Projectile, ProjectileKind};
+use wolf3d_rs::pushwall::PushWallState;
+use wolf3d_rs::rng::Rng;
+use wolf3d_rs::save::{SaveGame, SAVE_VERSION};
+use wolf3d_rs::world::World;
+
+use common::{slide_doors, world1};
+
+/// map1 with some of everything changed: a door mid-slide, a finished push wall, a dead enemy,
+/// an alerted one, a picked-up item, a rocket in flight and a wounded, dying player mid-shot
+/// with noclip and god mode on.
+fn played_map1() -> World {
+    let mut world = World::load("map1", Difficulty::Medium).expect("map1 loads");
+    world.doors.values_mut().next().unwrap().activate();
+    slide_doors(&mut world.doors, 10);
+    let (map, doors) = (&mut world.map, &world.doors);
+    let (x, y) = (map.pushwalls[0].x, map.pushwalls[0].y);
+    assert!(map.push_wall(doors, x, y, (0, 1)) || map.push_wall(doors, x, y, (1, 0)) || map.push_wall(doors, x, y, (0, -1)));
+    for _ in 0..200 {
+        map.update_pushwalls();
+    }
+    let ids = world.enemies.ids();
+    world.enemies[ids[0]].alive = false;
+    let e = &mut world.enemies[ids[1]];
+    e.x += 0.25;
+    e.alert = true;
+    e.facing = Dir::West;
+    world.items.values_mut().nth(2).unwrap().picked = true;
+    let rocket = Projectile::aimed(ProjectileKind::Rocket, Owner::Enemy(ids[1]), 30, (9.5, 2.5), (3.25, 4.75));
+    world.projectiles.spawn(rocket);
+
+    let player = &mut world.player;
+    *player = Player::new(3.25, 4.75, 1.5);
+    player.health = 37;
+    player.armor = 12;
+    player.score = 4200;
+    player.ammo = 55;
+    player.weapon = Weapon::MachineGun;
+    player.keys = 1;
+    player.fire_cooldown = 3;
+    player.noclip = true;
+    player.god = true;
+    player.state = LifeState::Dying { ticks: 17 };
+    world.level_ticks = 99;
+    world.rng = Rng::new(7);
+    world
+}
+
+#[test]
+fn text_round_trip() {
+    let mut world = played_map1();
+    world.level_ticks = 1234;
+    world.rng = Rng::new(0xDEAD_BEEF);
+    let progress = Progress { index: 1, return_to: Some(0) };
+    let save = SaveGame::capture(progress, &world);
+    let text = save.to_text();
+    let parsed = SaveGame::parse(&text).expect("save parses");
+    assert_eq!(parsed.to_text(), text);
+    assert_eq!(parsed.progress, progress);
+    assert_eq!(parsed.level_ticks, 1234);
+    assert_eq!(parsed.rng, 0xDEAD_BEEF);
+}
+
+#[test]
+fn restore_matches_captured_world() {
+    let world = played_map1();
+    let save = SaveGame::capture(Progress::start(), &world);
+    let restored = SaveGame::parse(&save.to_text()).unwrap().restore().expect("save restores");
+    let (map, player) = (&world.map, &world.player);
+
+    assert_eq!(restored.map.walls, map.walls);
+    assert_eq!(restored.map.pushwalls[0].state, PushWallState::Done);
+    for (a, b) in restored.doors.values().zip(world.doors.values()) {
+        assert_eq!((a.open, a.action), (b.open, b.action));
+    }
+    for ((ia, a), (ib, b)) in restored.enemies.iter().zip(world.enemies.iter()) {
+        assert_eq!(ia, ib);
+        assert_eq!((a.x, a.y, a.alive, a.alert, a.facing), (b.x, b.y, b.alive, b.alert, b.facing));
+    }
+    for (a, b) in restored.items.values().zip(world.items.values()) {
+        assert_eq!(a.picked, b.picked);
+    }
+    assert_eq!(restored.projectiles.values().collect::<Vec<_>>(), world.projectiles.values().collect::<Vec<_>>());
+    assert_eq!(restored.kills(), world.kills());
+    assert_eq!(restored.map.secrets_found(), map.secrets_found());
+    assert_eq!((restored.level_ticks, restored.rng), (99, Rng::new(7)));
+
+    let restored_player = &restored.player;
+    assert_eq!((restored_player.x, restored_player.y, restored_player.angle), (player.x, player.y, player.angle));
+    assert_eq!(restored_player.health, 37);
+    assert_eq!(restored_player.weapon, Weapon::MachineGun);
+    assert_eq!(restored_player.state, LifeState::Dying { ticks: 17 });
+    assert_eq!(restored_player.keys, 1);
+    assert_eq!((restored_player.fire_cooldown, restored_player.noclip, restored_player.god), (3, true, true));
+
+    let again = SaveGame::capture(Progress::start(), &restored);
+    assert_eq!(again.to_text(), save.to_text());
+}
+
+#[test]
+fn moving_pushwall_restores_open_tile() {
+    let mut world = world1();
+    let (map, doors) = (&mut world.map, &world.doors);
+    let (x, y) = (map.pushwalls[0].x, map.pushwalls[0].y);
+    assert!(map.push_wall(doors, x, y, (0, 1)) || map.push_wall(doors, x, y, (1, 0)) || map.push_wall(doors, x, y, (0, -1)));
+    for _ in 0..30 {
+        map.update_pushwalls();
+    }
+    let restored = SaveGame::capture(Progress::start(), &world).restore().unwrap();
+    assert_eq!(restored.map.cell_at(x, y), Cell::Empty);
+    assert_eq!(restored.map.pushwalls[0].offset, world.map.pushwalls[0].offset);
+    assert!(restored.map.moving_pushwall_at(x, y).is_some());
+}
+
+#[test]
+fn rejects_other_versions_and_garbage() {
+    let text = SaveGame::capture(Progress::start(), &played_map1()).to_text();
+    let future = text.replacen(&format!(" {SAVE_VERSION}\n"), &format!(" {}\n", SAVE_VERSION + 1), 1);
+    assert!(SaveGame::parse(&future).unwrap_err().contains("version"));
+    assert!(SaveGame::parse("hello").is_err());
+    assert!(SaveGame::parse(&text.replace("enemy ", "enemy x")).is_err());
+    assert!(SaveGame::parse(&text.replace(" 1:0 ", " 1 ")).is_err());
+}
+
+#[test]
+fn restore_rejects_mismatched_map() {
+    let mut save = SaveGame::capture(Progress::start(), &played_map1());
+    save.map = "map2".to_string();
+    assert!(save.restore().is_err());
+}
+
+#[test]
+fn difficulty_round_trips_and_picks_spawns() {
+    let baby = World::load("map1", Difficulty::Baby).unwrap();
+    let hard = World::load("map1", Difficulty::Hard).unwrap();
+    assert!(baby.enemies.len() < hard.enemies.len());
+
+    let save = SaveGame::capture(Progress::start(), &hard);
+    let parsed = SaveGame::parse(&save.to_text()).unwrap();
+    assert_eq!(parsed.difficulty, Difficulty::Hard);
+    let restored = parsed.restore().expect("hard save restores with hard spawns");
+    assert_eq!(restored.enemies.len(), hard.enemies.len());
+}
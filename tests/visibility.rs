@@ -0,0 +1,63 @@
+mod common;
+
+use wolf3d_rs::map::Cell;
+
+use common::map1;
+
+#[test]
+fn raycast_reports_hit_point_and_normal() {
+    let (map, doors) = map1();
+    let hit = map.raycast(&doors, (1.5, 1.5), (2.0, 0.0), 100.0).expect("the east wall");
+    assert_eq!((hit.map_x, hit.map_y), (15, 1));
+    assert!((hit.point.0 - 15.0).abs() < 1e-4 && (hit.point.1 - 1.5).abs() < 1e-4);
+    assert_eq!(hit.normal, (-1.0, 0.0));
+    assert!((hit.distance - 13.5).abs() < 1e-4);
+
+    let hit = map.raycast(&doors, (1.5, 1.5), (0.0, -1.0), 100.0).unwrap();
+    assert_eq!(hit.normal, (0.0, 1.0));
+    assert!((hit.point.1 - 1.0).abs() < 1e-4);
+
+    assert!(map.raycast(&doors, (1.5, 1.5), (1.0, 0.0), 5.0).is_none());
+    assert!(map.raycast(&doors, (1.5, 1.5), (0.0, 0.0), 5.0).is_none());
+}
+
+#[test]
+fn line_of_sight_is_blocked_by_walls() {
+    let (map, doors) = map1();
+    assert!(map.line_of_sight(&doors, (1.5, 1.5), (14.5, 1.5)));
+    assert!(map.line_of_sight(&doors, (14.5, 1.5), (1.5, 1.5)));
+    // The brick wall at (3, 4) is between these two.
+    assert!(!map.line_of_sight(&doors, (2.5, 4.5), (4.5, 4.5)));
+}
+
+#[test]
+fn doors_block_sight_by_how_far_they_are_open() {
+    let (map, mut doors) = map1();
+    // Straight through the middle of the door at (7, 7).
+    let (north, south) = ((7.5, 6.5), (7.5, 8.5));
+    assert!(!map.line_of_sight(&doors, north, south));
+    let hit = map.raycast(&doors, north, (0.0, 1.0), 10.0).unwrap();
+    assert!(matches!(hit.cell, Cell::Door(_)));
+    assert!((hit.point.1 - 7.5).abs() < 1e-4, "door slabs sit mid-tile");
+
+    map.door_at_mut(&mut doors, 7, 7).unwrap().open = 0.4;
+    assert!(!map.line_of_sight(&doors, north, south));
+    map.door_at_mut(&mut doors, 7, 7).unwrap().open = 0.6;
+    assert!(map.line_of_sight(&doors, north, south));
+}
+
+#[test]
+fn visible_tiles_stop_at_walls_and_doors() {
+    let (map, mut doors) = map1();
+    let seen = map.visible_tiles(&doors, (1.5, 1.5), 100.0);
+    assert!(seen.contains(&(1, 1)) && seen.contains(&(14, 1)));
+    assert!(seen.contains(&(15, 1)), "bounding walls are visible");
+    assert!(!seen.contains(&(11, 11)), "the exit room is walled off");
+    assert!(!seen.contains(&(7, 8)), "behind the closed door");
+    assert!(seen.windows(2).all(|w| (w[0].1, w[0].0) < (w[1].1, w[1].0)));
+
+    assert!(!map.visible_tiles(&doors, (1.5, 1.5), 3.0).contains(&(14, 1)));
+
+    map.door_at_mut(&mut doors, 7, 7).unwrap().open = 1.0;
+    assert!(map.visible_tiles(&doors, (7.5, 6.5), 100.0).contains(&(7, 8)));
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use wolf3d_rs::audio::SoundId;
+use wolf3d_rs::difficulty::Difficulty;
+use wolf3d_rs::entity::Entities;
+use wolf3d_rs::player::{Player, Weapon};
+use wolf3d_rs::rng::Rng;
+use wolf3d_rs::world::{Event, TickInput, World, SCHEDULE};
+
+use common::world1;
+
+#[test]
+fn despawned_ids_go_stale_when_slots_are_reused() {
+    let mut store: Entities<&str> = Entities::default();
+    let a = store.spawn("a");
+    let b = store.spawn("b");
+    assert_eq!(store.despawn(a), Some("a"));
+    assert_eq!(store.despawn(a), None);
+
+    let c = store.spawn("c");
+    assert_eq!(c.index(), a.index(), "the free slot is reused");
+    assert_ne!(c, a);
+    assert_eq!(store.get(a), None);
+    assert_eq!((store[b], store[c]), ("b", "c"));
+    assert_eq!(store.len(), 2);
+
+    store.retain(|_, v| *v != "b");
+    assert!(!store.contains(b));
+    assert_eq!(store.ids(), vec![c]);
+}
+
+#[test]
+fn ticks_run_every_system_and_count_level_time() {
+    let mut world = world1();
+    let names: Vec<&str> = world.schedule.iter().map(|(name, _)| *name).collect();
+    assert_eq!(names, SCHEDULE.iter().map(|(name, _)| *name).collect::<Vec<_>>());
+
+    let start = (world.player.x, world.player.y);
+    let forward = TickInput { forward: true, ..TickInput::default() };
+    for _ in 0..10 {
+        world.tick(&forward);
+    }
+    assert_eq!(world.level_ticks, 10);
+    assert_ne!((world.player.x, world.player.y), start);
+
+    // A world without a player system ignores movement.
+    world.schedule.retain(|(name, _)| *name != "player");
+    let at = (world.player.x, world.player.y);
+    world.tick(&forward);
+    assert_eq!((world.player.x, world.player.y), at);
+}
+
+#[test]
+fn firing_reports_gunfire_and_wakes_the_hall() {
+    let mut world = world1();
+    world.player = Player::new(2.5, 2.5, 0.0);
+    let lights = world.lighting.point_lights.len();
+    let events = world.tick(&TickInput { fire: true, ..TickInput::default() });
+    assert_eq!(events.first(), Some(&Event::Sound(SoundId::Gunfire)));
+    assert!(events.iter().any(|e| matches!(e, Event::SoundAt(..))), "no alert sounds in {events:?}");
+    assert!(world.enemies.values().any(|e| e.alert));
+    assert_eq!(world.lighting.point_lights.len(), lights + 1, "muzzle flash");
+}
+
+#[test]
+fn stabs_roll_their_damage_from_the_world_rng() {
+    let stab = |seed| {
+        let mut world = world1();
+        world.rng = Rng::new(seed);
+        world.player = Player::new(2.5, 2.5, 0.0);
+        world.player.ammo = 0;
+        let id = world.enemies.ids()[0];
+        let e = &mut world.enemies[id];
+        (e.x, e.y, e.health) = (3.5, 2.5, 100);
+        world.tick(&TickInput { fire: true, ..TickInput::default() });
+        100 - world.enemies[id].health
+    };
+    let dealt: Vec<i32> = (1..=8).map(stab).collect();
+    let base = Weapon::Knife.damage();
+    assert!(dealt.iter().all(|d| (base / 2..=base * 3 / 2).contains(d)), "{dealt:?}");
+    assert_eq!(stab(1), dealt[0], "the same seed rolls the same");
+    assert!(dealt.iter().any(|&d| d != dealt[0]), "every seed rolled {}", dealt[0]);
+}
+
+#[test]
+fn entering_a_map_keeps_the_player_and_respawns_entities() {
+    let mut world = World::load("map1", Difficulty::Hard).unwrap();
+    world.player.score = 1234;
+    let first = world.enemies.ids()[0];
+    world.damage_enemy(first, 1000);
+    world.enter_map("map2").unwrap();
+    assert_eq!((world.name.as_str(), world.difficulty, world.player.score), ("map2", Difficulty::Hard, 1234));
+    assert_eq!((world.player.x, world.player.y, world.player.angle), world.map.player_start);
+    assert_eq!(world.kills(), 0);
+    assert_eq!(world.level_ticks, 0);
+}
+
+#[test]
+fn kill_all_scores_drops_and_sounds_every_death() {
+    let mut world = world1();
+    let first = world.enemies.ids()[0];
+    world.damage_enemy(first, 1000);
+    let living: Vec<_> = world.enemies.values().filter(|e| e.alive).map(|e| world.bestiary.get(e.kind)).collect();
+    let score: u32 = living.iter().map(|d| d.score).sum();
+    let drops = living.iter().filter(|d| d.drop.is_some()).count();
+    let deaths = living.len();
+    let items = world.items.len();
+
+    let events = world.kill_all();
+    assert_eq!(world.kills(), world.kills_total());
+    assert_eq!(world.player.score, score, "the corpse already down scores nothing");
+    assert_eq!(world.items.len(), items + drops);
+    assert_eq!(events.iter().filter(|e| matches!(e, Event::SoundAt(..))).count(), deaths);
+}